@@ -1,3 +1,4 @@
+use farm_engine::game::inventory::Item;
 use farm_engine::game::{Direction, Game, GameOptions};
 use farm_engine::world::entities::EntityType;
 
@@ -5,7 +6,11 @@ fn main() {
     let mut game = Game::new(GameOptions {
         world_width: 5,
         world_height: 5,
+        ..Default::default()
     });
+    game.add_item(Item::Hay, 10);
+    game.add_item(Item::Wood, 10);
+    game.add_item(Item::Carrot, 10);
 
     println!("Initial game state:");
     println!("{}", game);
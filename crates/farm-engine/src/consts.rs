@@ -3,6 +3,8 @@ pub const BUSH_GROWTH_TIME: f64 = 2.0;
 pub const CARROT_GROWTH_TIME: f64 = 3.0;
 pub const PUMPKIN_GROWTH_TIME: f64 = 4.0;
 pub const TREE_GROWTH_TIME: f64 = 5.0;
+pub const SUNFLOWER_GROWTH_TIME: f64 = 5.0;
+pub const CACTUS_GROWTH_TIME: f64 = 1.0;
 
 pub const DRONE_SYMBOL: &str = "D";
 pub const GRASSLAND_EMPTY_SYMBOL: &str = ".";
@@ -12,4 +14,90 @@ pub const BUSH_SYMBOL: &str = "B";
 pub const TREE_SYMBOL: &str = "T";
 pub const CARROT_SYMBOL: &str = "C";
 pub const PUMPKIN_SYMBOL: &str = "P";
+pub const SUNFLOWER_SYMBOL: &str = "F";
+pub const CACTUS_SYMBOL: &str = "X";
+pub const DEAD_PUMPKIN_SYMBOL: &str = "p";
+pub const HEDGE_SYMBOL: &str = "#";
+pub const TREASURE_SYMBOL: &str = "$";
+pub const APPLE_SYMBOL: &str = "A";
 pub const UNKNOWN_SYMBOL: &str = "?";
+
+pub const TICKS_PER_SECOND: u64 = 1000;
+pub const MOVE_TICKS: u64 = 200;
+pub const TILL_TICKS: u64 = 200;
+pub const PLANT_TICKS: u64 = 200;
+pub const HARVEST_TICKS: u64 = 200;
+pub const USE_ITEM_TICKS: u64 = 200;
+pub const SWAP_TICKS: u64 = 200;
+pub const CHANGE_HAT_TICKS: u64 = 200;
+pub const SPAWN_DRONE_TICKS: u64 = 200;
+pub const QUERY_TICKS: u64 = 1;
+
+pub const DEFAULT_WORLD_SIZE: usize = 10;
+pub const DEFAULT_MAX_DRONES: usize = 16;
+pub const DEFAULT_SOIL_RATIO: f64 = 0.3;
+pub const DEFAULT_PLANTED_RATIO: f64 = 0.5;
+pub const DEFAULT_PATCH_SIZE: usize = 4;
+
+pub const GRASS_HAY_YIELD: u64 = 1;
+pub const BUSH_WOOD_YIELD: u64 = 1;
+pub const TREE_WOOD_YIELD: u64 = 5;
+pub const CARROT_YIELD: u64 = 1;
+pub const PUMPKIN_YIELD: u64 = 1;
+pub const SUNFLOWER_POWER_YIELD: u64 = 1;
+pub const LARGEST_SUNFLOWER_MULTIPLIER: u64 = 5;
+pub const CACTUS_YIELD: u64 = 1;
+pub const GIANT_PUMPKIN_MAX_MULTIPLIER: u64 = 6;
+pub const COMPANION_YIELD_MULTIPLIER: u64 = 5;
+pub const TREASURE_GOLD_PER_TILE: u64 = 1;
+pub const BONES_PER_TAIL_SQUARE: u64 = 1;
+
+pub const CARROT_HAY_COST: u64 = 1;
+pub const CARROT_WOOD_COST: u64 = 1;
+pub const PUMPKIN_CARROT_COST: u64 = 1;
+pub const SUNFLOWER_CARROT_COST: u64 = 1;
+
+pub const SUNFLOWER_MIN_PETALS: u32 = 7;
+pub const SUNFLOWER_MAX_PETALS: u32 = 15;
+
+pub const CACTUS_MIN_SIZE: u32 = 0;
+pub const CACTUS_MAX_SIZE: u32 = 9;
+
+pub const COMPANION_MAX_DISTANCE: usize = 3;
+
+pub const DEFAULT_PUMPKIN_DEATH_CHANCE: f64 = 0.2;
+
+pub const POWER_SPEEDUP: u64 = 2;
+pub const POWER_OPERATIONS_PER_UNIT: u64 = 30;
+
+pub const DEFAULT_SEED: u64 = 0;
+pub const DEFAULT_GROWTH_VARIANCE: f64 = 0.2;
+
+pub const MAX_WATER: f64 = 1.0;
+pub const WATER_PER_USE: f64 = 0.25;
+pub const WATER_DECAY_PER_SECOND: f64 = 0.01;
+pub const WATER_GROWTH_BONUS: f64 = 4.0;
+
+pub const FERTILIZER_GROWTH_TIME: f64 = 2.0;
+
+pub const TREE_ADJACENT_GROWTH_MULTIPLIER: f64 = 2.0;
+
+pub const UNLOCK_TICKS: u64 = 200;
+pub const UNLOCK_COST_GROWTH: u64 = 2;
+pub const SPEED_MAX_LEVEL: u32 = 4;
+pub const EXPAND_WORLD_SIZES: [usize; 7] = [4, 6, 8, 10, 12, 14, 16];
+pub const SPEED_UNLOCK_HAY_COST: u64 = 20;
+pub const EXPAND_UNLOCK_WOOD_COST: u64 = 20;
+pub const TILL_UNLOCK_HAY_COST: u64 = 10;
+pub const WATERING_UNLOCK_WOOD_COST: u64 = 20;
+pub const FERTILIZER_UNLOCK_CARROT_COST: u64 = 20;
+pub const SWAP_UNLOCK_WOOD_COST: u64 = 50;
+pub const MEASURE_UNLOCK_HAY_COST: u64 = 50;
+pub const CARROTS_UNLOCK_WOOD_COST: u64 = 20;
+pub const TREES_UNLOCK_WOOD_COST: u64 = 50;
+pub const PUMPKINS_UNLOCK_CARROT_COST: u64 = 50;
+pub const SUNFLOWERS_UNLOCK_CARROT_COST: u64 = 100;
+pub const CACTUS_UNLOCK_PUMPKIN_COST: u64 = 100;
+pub const POLYCULTURE_UNLOCK_HAY_COST: u64 = 200;
+pub const MAZES_UNLOCK_PUMPKIN_COST: u64 = 500;
+pub const DINOSAUR_UNLOCK_CACTUS_COST: u64 = 500;
@@ -4,32 +4,46 @@ use crate::{
     world::{entities::EntityType, tiles::GroundType},
 };
 
+/// Ground and entity drawn as `symbol` in the world grid. The drone symbol
+/// and entities shared by both grounds parse as grassland.
+pub(crate) fn parse_tile_symbol(symbol: &str) -> Option<(GroundType, Option<EntityType>)> {
+    let grassland = |entity| Some((GroundType::Grassland, entity));
+    let soil = |entity| Some((GroundType::Soil, entity));
+    match symbol {
+        DRONE_SYMBOL | GRASSLAND_EMPTY_SYMBOL => grassland(None),
+        GRASS_SYMBOL => grassland(Some(EntityType::Grass)),
+        BUSH_SYMBOL => grassland(Some(EntityType::Bush)),
+        TREE_SYMBOL => grassland(Some(EntityType::Tree)),
+        HEDGE_SYMBOL => grassland(Some(EntityType::Hedge)),
+        TREASURE_SYMBOL => grassland(Some(EntityType::Treasure)),
+        APPLE_SYMBOL => grassland(Some(EntityType::Apple)),
+        SOIL_EMPTY_SYMBOL => soil(None),
+        CARROT_SYMBOL => soil(Some(EntityType::Carrot)),
+        PUMPKIN_SYMBOL => soil(Some(EntityType::Pumpkin)),
+        SUNFLOWER_SYMBOL => soil(Some(EntityType::Sunflower)),
+        CACTUS_SYMBOL => soil(Some(EntityType::Cactus)),
+        DEAD_PUMPKIN_SYMBOL => soil(Some(EntityType::DeadPumpkin)),
+        _ => None,
+    }
+}
+
 impl std::fmt::Display for Game {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(
-            f,
-            "World Size: {}x{}",
-            self.get_world_size().0,
-            self.get_world_size().1
-        )?;
-        writeln!(
-            f,
-            "Drone Position: ({}, {})",
-            self.get_pos_x(),
-            self.get_pos_y()
-        )?;
-
-        if let Ok(ground_type) = self.get_ground_type() {
-            writeln!(f, "Current Ground: {:?}", ground_type)?;
-        }
+        let (width, height) = (self.world().width(), self.world().height());
+        let (drone_x, drone_y) = self.drone_position();
+
+        writeln!(f, "World Size: {}x{}", width, height)?;
+        writeln!(f, "Drone Position: ({}, {})", drone_x, drone_y)?;
 
-        if let Ok(Some(entity_type)) = self.get_entity_type() {
-            writeln!(f, "Current Entity: {:?}", entity_type)?;
-            if let Ok(can_harvest) = self.can_harvest() {
-                writeln!(f, "Can Harvest: {}", can_harvest)?;
+        if let Ok(tile) = self.world().get_tile(drone_x, drone_y) {
+            writeln!(f, "Current Ground: {:?}", tile.ground_type())?;
+
+            if let Some(entity) = tile.entity() {
+                writeln!(f, "Current Entity: {:?}", entity.entity_type())?;
+                writeln!(f, "Can Harvest: {}", tile.is_grown(self.now()))?;
+            } else {
+                writeln!(f, "Current Entity: None")?;
             }
-        } else {
-            writeln!(f, "Current Entity: None")?;
         }
 
         writeln!(f)?;
@@ -43,9 +57,8 @@ impl std::fmt::Display for Game {
 
 impl Game {
     pub fn print_world_grid(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let (width, height) = self.get_world_size();
-        let drone_x = self.get_pos_x();
-        let drone_y = self.get_pos_y();
+        let (width, height) = (self.world().width(), self.world().height());
+        let drones = self.drone_positions();
 
         write!(f, "  ")?;
         for x in 0..width {
@@ -56,7 +69,7 @@ impl Game {
         for display_y in (0..height).rev() {
             write!(f, "{:2} ", display_y)?;
             for x in 0..width {
-                let tile_char = if x == drone_x && display_y == drone_y {
+                let tile_char = if drones.contains(&(x, display_y)) {
                     DRONE_SYMBOL
                 } else {
                     match self.world().get_tile(x, display_y) {
@@ -65,12 +78,19 @@ impl Game {
                                 EntityType::Grass => GRASS_SYMBOL,
                                 EntityType::Bush => BUSH_SYMBOL,
                                 EntityType::Tree => TREE_SYMBOL,
+                                EntityType::Hedge => HEDGE_SYMBOL,
+                                EntityType::Treasure => TREASURE_SYMBOL,
+                                EntityType::Apple => APPLE_SYMBOL,
                                 _ => UNKNOWN_SYMBOL,
                             },
                             (GroundType::Grassland, None) => GRASSLAND_EMPTY_SYMBOL,
                             (GroundType::Soil, Some(entity)) => match entity.entity_type() {
                                 EntityType::Carrot => CARROT_SYMBOL,
                                 EntityType::Pumpkin => PUMPKIN_SYMBOL,
+                                EntityType::Sunflower => SUNFLOWER_SYMBOL,
+                                EntityType::Cactus => CACTUS_SYMBOL,
+                                EntityType::DeadPumpkin => DEAD_PUMPKIN_SYMBOL,
+                                EntityType::Apple => APPLE_SYMBOL,
                                 _ => UNKNOWN_SYMBOL,
                             },
                             (GroundType::Soil, None) => SOIL_EMPTY_SYMBOL,
@@ -93,21 +113,26 @@ impl Game {
         writeln!(f, "  {} = Tree", TREE_SYMBOL)?;
         writeln!(f, "  {} = Carrot", CARROT_SYMBOL)?;
         writeln!(f, "  {} = Pumpkin", PUMPKIN_SYMBOL)?;
+        writeln!(f, "  {} = Sunflower", SUNFLOWER_SYMBOL)?;
+        writeln!(f, "  {} = Cactus", CACTUS_SYMBOL)?;
+        writeln!(f, "  {} = Dead Pumpkin", DEAD_PUMPKIN_SYMBOL)?;
+        writeln!(f, "  {} = Hedge", HEDGE_SYMBOL)?;
+        writeln!(f, "  {} = Treasure", TREASURE_SYMBOL)?;
+        writeln!(f, "  {} = Apple", APPLE_SYMBOL)?;
 
         Ok(())
     }
 
     pub fn get_world_grid_string(&self) -> String {
-        let (width, height) = self.get_world_size();
-        let drone_x = self.get_pos_x();
-        let drone_y = self.get_pos_y();
+        let (width, height) = (self.world().width(), self.world().height());
+        let drones = self.drone_positions();
 
         let mut grid = String::new();
 
         // Display from top to bottom, but with y-axis starting from bottom
         for display_y in (0..height).rev() {
             for x in 0..width {
-                let tile_char = if x == drone_x && display_y == drone_y {
+                let tile_char = if drones.contains(&(x, display_y)) {
                     DRONE_SYMBOL
                 } else {
                     match self.world().get_tile(x, display_y) {
@@ -116,12 +141,19 @@ impl Game {
                                 EntityType::Grass => GRASS_SYMBOL,
                                 EntityType::Bush => BUSH_SYMBOL,
                                 EntityType::Tree => TREE_SYMBOL,
+                                EntityType::Hedge => HEDGE_SYMBOL,
+                                EntityType::Treasure => TREASURE_SYMBOL,
+                                EntityType::Apple => APPLE_SYMBOL,
                                 _ => UNKNOWN_SYMBOL,
                             },
                             (GroundType::Grassland, None) => GRASSLAND_EMPTY_SYMBOL,
                             (GroundType::Soil, Some(entity)) => match entity.entity_type() {
                                 EntityType::Carrot => CARROT_SYMBOL,
                                 EntityType::Pumpkin => PUMPKIN_SYMBOL,
+                                EntityType::Sunflower => SUNFLOWER_SYMBOL,
+                                EntityType::Cactus => CACTUS_SYMBOL,
+                                EntityType::DeadPumpkin => DEAD_PUMPKIN_SYMBOL,
+                                EntityType::Apple => APPLE_SYMBOL,
                                 _ => UNKNOWN_SYMBOL,
                             },
                             (GroundType::Soil, None) => SOIL_EMPTY_SYMBOL,
@@ -136,9 +168,33 @@ impl Game {
             }
         }
 
+        // Apples look the same on either ground, so soil under one is noted
+        // in the annotation format `World::from_ascii` reads.
+        for (x, y) in self.apples_on_soil() {
+            if !drones.contains(&(x, y)) {
+                grid.push_str(&format!("\n@{x},{y} ground=soil"));
+            }
+        }
+
         grid
     }
 
+    fn apples_on_soil(&self) -> Vec<(usize, usize)> {
+        let (width, height) = (self.world().width(), self.world().height());
+        (0..height)
+            .rev()
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|&(x, y)| {
+                self.world().get_tile(x, y).is_ok_and(|tile| {
+                    tile.ground_type() == &GroundType::Soil
+                        && tile
+                            .entity()
+                            .is_some_and(|entity| entity.entity_type() == &EntityType::Apple)
+                })
+            })
+            .collect()
+    }
+
     pub fn get_tile_info(&self, x: usize, y: usize) -> Result<String, crate::error::EngineError> {
         let tile = self.world().get_tile(x, y)?;
         let mut info = format!("Tile ({}, {}): ", x, y);
@@ -146,7 +202,7 @@ impl Game {
         match (tile.ground_type(), tile.entity()) {
             (GroundType::Grassland, Some(entity)) => {
                 info.push_str(&format!("Grassland with {:?}", entity.entity_type()));
-                if entity.is_grown() {
+                if tile.is_grown(self.now()) {
                     info.push_str(" (grown)");
                 } else {
                     info.push_str(" (growing)");
@@ -157,7 +213,7 @@ impl Game {
             }
             (GroundType::Soil, Some(entity)) => {
                 info.push_str(&format!("Soil with {:?}", entity.entity_type()));
-                if entity.is_grown() {
+                if tile.is_grown(self.now()) {
                     info.push_str(" (grown)");
                 } else {
                     info.push_str(" (growing)");
@@ -174,18 +230,12 @@ impl Game {
 
 #[cfg(test)]
 mod tests {
-    use crate::game::{Game, GameOptions};
-
-    fn create_test_game() -> Game {
-        Game::new(GameOptions {
-            world_width: 3,
-            world_height: 3,
-        })
-    }
+    use crate::game::testing::TestGame;
+    use crate::world::entities::EntityType;
 
     #[test]
     fn can_display_game() {
-        let game = create_test_game();
+        let game = TestGame::new(3, 3).build();
         let display_string = format!("{}", game);
 
         assert!(display_string.contains("World Size: 3x3"));
@@ -196,7 +246,7 @@ mod tests {
 
     #[test]
     fn can_get_world_grid_string() {
-        let game = create_test_game();
+        let game = TestGame::new(3, 3).build();
         let grid_string = game.get_world_grid_string();
 
         let lines: Vec<&str> = grid_string.lines().collect();
@@ -207,10 +257,21 @@ mod tests {
 
     #[test]
     fn can_get_tile_info() {
-        let game = create_test_game();
+        let game = TestGame::new(3, 3).build();
         let tile_info = game.get_tile_info(0, 0).unwrap();
 
         assert!(tile_info.contains("Tile (0, 0)"));
         assert!(tile_info.contains("Grassland"));
     }
+
+    #[test]
+    fn tile_info_reports_growth_against_game_clock() {
+        let mut game = TestGame::new(3, 3).build();
+        game.plant(EntityType::Bush).unwrap();
+
+        assert!(game.get_tile_info(0, 0).unwrap().contains("(growing)"));
+
+        game.advance_time(EntityType::Bush.growth_time());
+        assert!(game.get_tile_info(0, 0).unwrap().contains("(grown)"));
+    }
 }
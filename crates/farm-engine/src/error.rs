@@ -1,5 +1,8 @@
 use thiserror::Error;
 
+use crate::game::inventory::Item;
+use crate::game::unlock::Unlock;
+use crate::game::{Direction, DroneId};
 use crate::world::entities::EntityType;
 use crate::world::tiles::GroundType;
 
@@ -11,6 +14,8 @@ pub enum EngineError {
     FailedToCreateWorld(#[from] ndarray::ShapeError),
     #[error("Entity does not have a type. Please set it using the `entity_type` method.")]
     EntityWithoutType,
+    #[error("Entity {0:#?} cannot be planted.")]
+    EntityNotPlantable(EntityType),
     #[error("Entity {0:#?} is not allowed on this ground type. {1:#?}")]
     EntityNotAllowedOnGroundType(EntityType, GroundType),
     #[error("Drone is out of bounds. ({x}, {y})")]
@@ -19,4 +24,36 @@ pub enum EngineError {
     EntityNotGrown,
     #[error("Entity is not planted. Please plant it first.")]
     EntityNotPlanted,
+    #[error("Not enough {item:?}. Needed {needed}, have {have}.")]
+    InsufficientResources { item: Item, needed: u64, have: u64 },
+    #[error("Item {0:?} cannot be used.")]
+    ItemNotUsable(Item),
+    #[error("Drone is blocked by a wall to the {0:?}.")]
+    BlockedByWall(Direction),
+    #[error("Drone is blocked by its tail to the {0:?}.")]
+    BlockedByTail(Direction),
+    #[error("{0:?} is not unlocked yet.")]
+    NotUnlocked(Unlock),
+    #[error("{0:?} is already fully unlocked.")]
+    FullyUnlocked(Unlock),
+    #[error("World is already at least {0}x{0}, expanding would not grow it.")]
+    WorldAlreadyExpanded(usize),
+    #[error("World cannot be {width}x{height}.")]
+    InvalidWorldSize { width: usize, height: usize },
+    #[error("Cannot have more than {0} drones.")]
+    DroneLimitReached(usize),
+    #[error("Drone {0:?} does not exist.")]
+    UnknownDrone(DroneId),
+    #[error("Another drone already acted on tile ({x}, {y}) this tick.")]
+    TileConflict { x: usize, y: usize },
+    #[error("Invalid ASCII map on line {line}. {reason}")]
+    InvalidAsciiMap { line: usize, reason: String },
+    #[error("Replay diverged at action {index}. {reason}")]
+    ReplayDiverged { index: usize, reason: String },
+    #[cfg(feature = "serde")]
+    #[error("Failed to encode or decode JSON. {0}")]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "serde")]
+    #[error("Failed to encode or decode binary state. {0}")]
+    Binary(#[from] bincode::Error),
 }
@@ -1,11 +1,53 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
 use crate::{
+    consts,
     error::EngineError,
-    game::drone::Drone,
-    world::{entities::EntityType, tiles::GroundType, World},
+    game::{
+        action::Action,
+        clock::{Clock, SimulatedClock},
+        cost::{ticks_to_duration, Operation, OperationCosts},
+        drone::Drone,
+        event::{Event, Observer, ObserverId},
+        inventory::{Inventory, Item},
+        log::ActionLog,
+        pumpkin::giant_pumpkin_multiplier,
+        unlock::{Unlock, Unlocks},
+    },
+    world::{
+        entities::{Companion, Entity, EntityType},
+        tiles::GroundType,
+        World,
+    },
 };
 
+pub mod action;
+mod cactus;
+pub mod clock;
+mod companion;
+pub mod cost;
+mod dinosaur;
 pub(crate) mod drone;
+pub mod event;
+pub mod inventory;
+pub mod log;
+mod maze;
+#[cfg(feature = "serde")]
+mod persistence;
+mod pumpkin;
+pub mod scheduler;
+pub mod snapshot;
+#[cfg(test)]
+pub(crate) mod testing;
+mod tree;
+pub mod unlock;
 
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     North,
     East,
@@ -13,65 +55,588 @@ pub enum Direction {
     West,
 }
 
+/// Handle to one of the drones in a `Game`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DroneId(usize);
+
+/// What the drone wears. The dinosaur hat drags a tail that grows with
+/// every apple eaten.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Hat {
+    #[default]
+    Straw,
+    Dinosaur,
+}
+
+/// Result of `Game::measure`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Measurement {
+    /// Type-specific size of the measured entity.
+    Size(u32),
+    /// Position of the apple the dinosaur should eat next.
+    Position(usize, usize),
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+}
+
 pub struct GameOptions {
     pub world_width: usize,
     pub world_height: usize,
+    pub operation_costs: OperationCosts,
+    pub seed: u64,
+    /// Fraction by which each planted entity's growth time may deviate from
+    /// its base time, e.g. `0.2` grows plants in 80%..=120% of the base time.
+    pub growth_variance: f64,
+    /// Chance that a planted pumpkin dies when it finishes growing.
+    pub pumpkin_death_chance: f64,
+    /// Features available from the start. Defaults to everything, use
+    /// `Unlocks::new()` to simulate a run from scratch.
+    pub unlocks: Unlocks,
+    /// Most drones that can be on the farm at once, including the first one.
+    pub max_drones: usize,
+    /// Keeps an `ActionLog` of every action taken, for replays.
+    pub record_actions: bool,
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        Self {
+            world_width: consts::DEFAULT_WORLD_SIZE,
+            world_height: consts::DEFAULT_WORLD_SIZE,
+            operation_costs: OperationCosts::default(),
+            seed: consts::DEFAULT_SEED,
+            growth_variance: consts::DEFAULT_GROWTH_VARIANCE,
+            pumpkin_death_chance: consts::DEFAULT_PUMPKIN_DEATH_CHANCE,
+            unlocks: Unlocks::all(),
+            max_drones: consts::DEFAULT_MAX_DRONES,
+            record_actions: false,
+        }
+    }
 }
 
+/// A running game. It is `Send`, so its clock and observers must be too, and
+/// can be handed to another thread, e.g. to explore snapshots in parallel.
 pub struct Game {
     world: World,
-    drone: Drone,
+    drones: Vec<Drone>,
+    active_drone: DroneId,
+    max_drones: usize,
+    clock: Box<dyn Clock>,
+    operation_costs: OperationCosts,
+    elapsed_ticks: u64,
+    inventory: Inventory,
+    rng: ChaCha8Rng,
+    growth_variance: f64,
+    pumpkin_death_chance: f64,
+    powered_operations: u64,
+    deferred_ticks: Option<u64>,
+    unlocks: Unlocks,
+    action_log: Option<ActionLog>,
+    observers: Vec<(ObserverId, Box<dyn Observer>)>,
+    next_observer_id: usize,
 }
 
 impl Game {
     pub fn new(options: GameOptions) -> Self {
+        Self::with_clock(options, SimulatedClock::new())
+    }
+
+    pub fn with_clock(options: GameOptions, clock: impl Clock + 'static) -> Self {
         let world = World::new(options.world_width, options.world_height).unwrap();
-        let drone = Drone::new(0, 0);
-        Self { world, drone }
+        Self::with_world_and_clock(options, world, clock)
+    }
+
+    /// Starts a game on a prepared world, e.g. one from `WorldGenerator`.
+    /// The world's own size takes precedence over the one in `options`.
+    pub fn with_world(options: GameOptions, world: World) -> Self {
+        Self::with_world_and_clock(options, world, SimulatedClock::new())
+    }
+
+    /// Inverse of `get_world_grid_string`, see `World::from_ascii` for the
+    /// format. Every drone symbol places a drone, the first one is active.
+    pub fn from_grid_str(options: GameOptions, grid: &str) -> Result<Self, EngineError> {
+        let (world, positions) = World::parse_ascii(grid)?;
+        let mut game = Self::with_world(options, world);
+        if positions.len() > game.max_drones {
+            return Err(EngineError::DroneLimitReached(game.max_drones));
+        }
+        if !positions.is_empty() {
+            game.drones = positions
+                .into_iter()
+                .map(|(x, y)| Drone::new(x, y))
+                .collect();
+        }
+        Ok(game)
+    }
+
+    pub fn with_world_and_clock(
+        options: GameOptions,
+        world: World,
+        clock: impl Clock + 'static,
+    ) -> Self {
+        let mut game = Self {
+            world,
+            drones: vec![Drone::new(0, 0)],
+            active_drone: DroneId(0),
+            max_drones: options.max_drones.max(1),
+            clock: Box::new(clock),
+            operation_costs: options.operation_costs,
+            elapsed_ticks: 0,
+            inventory: Inventory::new(),
+            rng: ChaCha8Rng::seed_from_u64(options.seed),
+            growth_variance: options.growth_variance.clamp(0.0, 1.0),
+            pumpkin_death_chance: options.pumpkin_death_chance.clamp(0.0, 1.0),
+            powered_operations: 0,
+            deferred_ticks: None,
+            unlocks: options.unlocks,
+            action_log: options.record_actions.then(ActionLog::new),
+            observers: Vec::new(),
+            next_observer_id: 0,
+        };
+        // Every position is inside the world, so this cannot fail.
+        let _ = game.update_all_tree_growth();
+        game
+    }
+
+    pub fn now(&self) -> Duration {
+        self.clock.now()
+    }
+
+    fn drone(&self) -> &Drone {
+        &self.drones[self.active_drone.0]
+    }
+
+    fn mut_drone(&mut self) -> &mut Drone {
+        &mut self.drones[self.active_drone.0]
+    }
+
+    /// Adds a drone on the tile of the active drone. Actions keep applying
+    /// to the active drone until another one is selected.
+    pub fn spawn_drone(&mut self) -> Result<DroneId, EngineError> {
+        self.logged(Action::SpawnDrone, |game| game.spawn_drone_unlogged())
+    }
+
+    fn spawn_drone_unlogged(&mut self) -> Result<DroneId, EngineError> {
+        self.spend(Operation::SpawnDrone);
+        if self.drones.len() >= self.max_drones {
+            return Err(EngineError::DroneLimitReached(self.max_drones));
+        }
+        let (x, y) = self.drone_position();
+        self.drones.push(Drone::new(x, y));
+        Ok(DroneId(self.drones.len() - 1))
+    }
+
+    pub fn drone_ids(&self) -> impl Iterator<Item = DroneId> {
+        (0..self.drones.len()).map(DroneId)
+    }
+
+    pub fn active_drone(&self) -> DroneId {
+        self.active_drone
+    }
+
+    /// Makes `drone` the one that subsequent actions and queries apply to.
+    pub fn select_drone(&mut self, drone: DroneId) -> Result<(), EngineError> {
+        self.logged(Action::SelectDrone(drone), |game| {
+            game.select_drone_unlogged(drone)
+        })
+    }
+
+    fn select_drone_unlogged(&mut self, drone: DroneId) -> Result<(), EngineError> {
+        if drone.0 >= self.drones.len() {
+            return Err(EngineError::UnknownDrone(drone));
+        }
+        self.active_drone = drone;
+        Ok(())
+    }
+
+    /// Runs `action` with `drone` selected, then restores the previously
+    /// active drone.
+    pub fn with_drone<T>(
+        &mut self,
+        drone: DroneId,
+        action: impl FnOnce(&mut Game) -> T,
+    ) -> Result<T, EngineError> {
+        let previous = self.active_drone;
+        self.select_drone_unlogged(drone)?;
+        let result = action(self);
+        self.active_drone = previous;
+        Ok(result)
+    }
+
+    pub fn advance_time(&mut self, duration: Duration) {
+        let before = self.now();
+        self.clock.advance(duration);
+        self.emit_grown_since(before);
+    }
+
+    pub fn elapsed_ticks(&self) -> u64 {
+        self.elapsed_ticks
+    }
+
+    pub fn operation_costs(&self) -> &OperationCosts {
+        &self.operation_costs
+    }
+
+    /// Charges the tick cost of `operation`. Speed upgrades make operations
+    /// faster. While power is in the inventory, operations run faster still
+    /// and drain one unit every few operations.
+    fn spend(&mut self, operation: Operation) {
+        let mut ticks = self.operation_costs.ticks(operation);
+        ticks = ticks.div_ceil(1 + self.unlocks.level(Unlock::Speed) as u64);
+        if ticks > 0 && self.use_power() {
+            ticks = ticks.div_ceil(consts::POWER_SPEEDUP);
+        }
+        if let Some(deferred) = self.deferred_ticks.as_mut() {
+            *deferred += ticks;
+            return;
+        }
+        let before = self.now();
+        self.elapsed_ticks += ticks;
+        self.clock.advance(ticks_to_duration(ticks));
+        self.emit_grown_since(before);
+    }
+
+    /// Speeds up one operation with power if there is any, using up a unit
+    /// every `POWER_OPERATIONS_PER_UNIT` operations.
+    fn use_power(&mut self) -> bool {
+        if self.inventory.count(Item::Power) == 0 {
+            return false;
+        }
+        self.powered_operations += 1;
+        if self
+            .powered_operations
+            .is_multiple_of(consts::POWER_OPERATIONS_PER_UNIT)
+        {
+            let _ = self.inventory.remove(&[(Item::Power, 1)]);
+        }
+        true
+    }
+
+    /// Runs `action` at the current time and returns the ticks it cost
+    /// without advancing the clock, so several drones can act in the same
+    /// tick.
+    fn run_deferred<T>(&mut self, action: impl FnOnce(&mut Game) -> T) -> (T, u64) {
+        self.deferred_ticks = Some(0);
+        let result = action(self);
+        (result, self.deferred_ticks.take().unwrap_or_default())
+    }
+
+    /// Moves the clock forward to `tick` if it is still behind.
+    fn advance_to_tick(&mut self, tick: u64) {
+        if tick > self.elapsed_ticks {
+            let before = self.now();
+            self.clock
+                .advance(ticks_to_duration(tick - self.elapsed_ticks));
+            self.elapsed_ticks = tick;
+            self.emit_grown_since(before);
+        }
+    }
+
+    fn require(&self, unlock: Unlock) -> Result<(), EngineError> {
+        if self.unlocks.is_unlocked(unlock) {
+            Ok(())
+        } else {
+            Err(EngineError::NotUnlocked(unlock))
+        }
+    }
+
+    pub fn unlocks(&self) -> &Unlocks {
+        &self.unlocks
+    }
+
+    /// Buys the next level of `unlock` with items from the inventory.
+    pub fn unlock(&mut self, unlock: Unlock) -> Result<(), EngineError> {
+        self.logged(Action::Unlock(unlock), |game| game.unlock_unlogged(unlock))
+    }
+
+    fn unlock_unlogged(&mut self, unlock: Unlock) -> Result<(), EngineError> {
+        self.spend(Operation::Unlock);
+        let mut level = self.unlocks.level(unlock);
+        if level >= unlock.max_level() {
+            return Err(EngineError::FullyUnlocked(unlock));
+        }
+        let mut expand_to = None;
+        if unlock == Unlock::Expand {
+            // Sizes the world already has are skipped, along with their levels.
+            let smallest = self.world.width().min(self.world.height());
+            level += consts::EXPAND_WORLD_SIZES[level as usize..]
+                .iter()
+                .take_while(|&&size| size <= smallest)
+                .count() as u32;
+            let Some(&size) = consts::EXPAND_WORLD_SIZES.get(level as usize) else {
+                return Err(EngineError::WorldAlreadyExpanded(smallest));
+            };
+            expand_to = Some(size);
+        }
+        self.inventory.remove(&unlock.cost(level))?;
+        self.unlocks.set_level(unlock, level + 1);
+        if let Some(size) = expand_to {
+            self.resize_world_unlogged(
+                self.world.width().max(size),
+                self.world.height().max(size),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Resizes the world mid-run, pulling the drones and their tails back
+    /// inside the new bounds.
+    pub fn resize_world(&mut self, width: usize, height: usize) -> Result<(), EngineError> {
+        self.logged(Action::Resize(width, height), |game| {
+            game.resize_world_unlogged(width, height)
+        })
+    }
+
+    fn resize_world_unlogged(&mut self, width: usize, height: usize) -> Result<(), EngineError> {
+        self.world.resize(width, height)?;
+        // Tiles on the old edges no longer wrap around to each other.
+        self.update_all_tree_growth()?;
+        self.reroll_lost_companions()?;
+        for drone in &mut self.drones {
+            let (x, y) = (drone.x(), drone.y());
+            if x >= width || y >= height {
+                drone
+                    .set_position(x.min(width - 1), y.min(height - 1))
+                    .clear_tail();
+            } else {
+                drone.cut_tail(|x, y| x < width && y < height);
+            }
+        }
+        let dinosaurs = self.drones.iter().any(|drone| drone.hat() == Hat::Dinosaur);
+        if dinosaurs && self.apple_position().is_none() {
+            self.spawn_apple()?;
+        }
+        Ok(())
+    }
+
+    pub fn num_unlocked(&mut self, unlock: Unlock) -> u32 {
+        self.spend(Operation::NumUnlocked);
+        self.unlocks.level(unlock)
+    }
+
+    pub fn inventory(&self) -> &Inventory {
+        &self.inventory
+    }
+
+    /// Edits the inventory without recording an action.
+    #[cfg(test)]
+    pub(crate) fn mut_inventory(&mut self) -> &mut Inventory {
+        &mut self.inventory
+    }
+
+    /// Adds `amount` of `item` to the inventory, as when granting items
+    /// mid-run.
+    pub fn add_item(&mut self, item: Item, amount: u64) {
+        let _ = self.logged(Action::AddItem(item, amount), |game| {
+            game.inventory.add(item, amount);
+            Ok(())
+        });
+    }
+
+    pub fn num_items(&mut self, item: Item) -> u64 {
+        self.spend(Operation::NumItems);
+        self.inventory.count(item)
     }
 
     pub fn world(&self) -> &World {
         &self.world
     }
 
-    pub fn get_world_size(&self) -> (usize, usize) {
+    pub fn drone_position(&self) -> (usize, usize) {
+        (self.drone().x(), self.drone().y())
+    }
+
+    pub fn drone_positions(&self) -> Vec<(usize, usize)> {
+        self.drones
+            .iter()
+            .map(|drone| (drone.x(), drone.y()))
+            .collect()
+    }
+
+    pub fn drone_hat(&self) -> Hat {
+        self.drone().hat()
+    }
+
+    pub fn drone_tail(&self) -> &VecDeque<(usize, usize)> {
+        self.drone().tail()
+    }
+
+    pub fn get_world_size(&mut self) -> (usize, usize) {
+        self.spend(Operation::GetWorldSize);
         (self.world.width(), self.world.height())
     }
 
     pub fn plant(&mut self, entity_type: EntityType) -> Result<(), EngineError> {
-        let x = self.drone.x();
-        let y = self.drone.y();
+        self.logged(Action::Plant(entity_type.clone()), |game| {
+            game.plant_unlogged(entity_type)
+        })
+    }
+
+    fn plant_unlogged(&mut self, entity_type: EntityType) -> Result<(), EngineError> {
+        self.spend(Operation::Plant);
+        if let Some(unlock) = Unlock::for_entity(&entity_type) {
+            self.require(unlock)?;
+        }
+        let x = self.drone().x();
+        let y = self.drone().y();
+        let now = self.clock.now();
+        let cost = entity_type.planting_cost();
+        self.inventory.check(cost)?;
+        let mut entity = Entity::planted(
+            entity_type.clone(),
+            now,
+            &mut self.rng,
+            self.growth_variance,
+            self.pumpkin_death_chance,
+        );
+        let companion = self.roll_companion(&entity_type, x, y);
+        entity.set_companion(companion);
         let tile = self.world.mut_tile(x, y)?;
-        tile.set_entity(Some(entity_type.into()))?;
+        let eats_apple = tile
+            .entity()
+            .is_some_and(|entity| entity.entity_type() == &EntityType::Apple);
+        tile.set_entity(Some(entity))?;
+        self.inventory.remove(cost)?;
+        self.update_tree_growth_around(x, y)?;
+        if eats_apple && self.drones.iter().any(|drone| drone.hat() == Hat::Dinosaur) {
+            self.spawn_apple()?;
+        }
+        self.emit(|game| Event::Planted {
+            drone: game.active_drone,
+            position: (x, y),
+            entity_type,
+        });
         Ok(())
     }
 
     pub fn harvest(&mut self) -> Result<(), EngineError> {
-        let x = self.drone.x();
-        let y = self.drone.y();
-        if self.can_harvest()? {
-            let tile = self.world.mut_tile(x, y)?;
-            tile.set_entity(None)?;
+        self.logged(Action::Harvest, |game| game.harvest_unlogged())
+    }
+
+    fn harvest_unlogged(&mut self) -> Result<(), EngineError> {
+        self.spend(Operation::Harvest);
+        let x = self.drone().x();
+        let y = self.drone().y();
+        self.refresh_tile(x, y)?;
+        if self.is_harvestable_at(x, y)? {
+            let region = self.harvest_region(x, y)?;
+            let harvested = self.harvest_yield_at(x, y, region.len())?;
+            if let Some((item, amount)) = harvested {
+                self.inventory.add(item, amount);
+            }
+            let entity_type = self
+                .world
+                .get_tile(x, y)?
+                .entity()
+                .map(|entity| entity.entity_type().clone());
+            for &(x, y) in &region {
+                self.world.mut_tile(x, y)?.set_entity(None)?;
+                self.update_tree_growth_around(x, y)?;
+            }
+            if entity_type == Some(EntityType::Treasure) {
+                self.world.clear_walls();
+            }
+            if let Some(entity_type) = entity_type {
+                self.emit(|game| Event::Harvested {
+                    drone: game.active_drone,
+                    position: (x, y),
+                    entity_type,
+                    item: harvested.map(|(item, _)| item),
+                    amount: harvested.map_or(0, |(_, amount)| amount),
+                    cleared: region,
+                });
+            }
             Ok(())
         } else {
             Err(EngineError::EntityNotGrown)
         }
     }
 
-    pub fn can_harvest(&self) -> Result<bool, EngineError> {
-        let x = self.drone.x();
-        let y = self.drone.y();
-        let tile = self.world.get_tile(x, y)?;
-        if let Some(entity) = tile.entity() {
-            Ok(entity.is_grown())
-        } else {
-            Ok(false)
+    /// Tiles cleared by harvesting the entity at `(x, y)`.
+    fn harvest_region(&self, x: usize, y: usize) -> Result<Vec<(usize, usize)>, EngineError> {
+        match self.world.get_tile(x, y)?.entity().map(Entity::entity_type) {
+            Some(EntityType::Cactus) => self.sorted_cactus_region(x, y),
+            Some(EntityType::Pumpkin) => self.giant_pumpkin_region(x, y),
+            Some(EntityType::Treasure) => Ok(self.maze_region()),
+            _ => Ok(vec![(x, y)]),
+        }
+    }
+
+    fn harvest_yield_at(
+        &self,
+        x: usize,
+        y: usize,
+        region_size: usize,
+    ) -> Result<Option<(Item, u64)>, EngineError> {
+        let Some(entity) = self.world.get_tile(x, y)?.entity() else {
+            return Ok(None);
+        };
+        let Some((item, mut amount)) = entity.entity_type().harvest_yield() else {
+            return Ok(None);
+        };
+        if entity.entity_type() == &EntityType::Sunflower {
+            let largest = self
+                .world
+                .tiles()
+                .filter_map(|tile| tile.entity())
+                .filter(|entity| entity.entity_type() == &EntityType::Sunflower)
+                .filter_map(Entity::measure)
+                .max();
+            if entity.measure().is_some() && entity.measure() == largest {
+                amount *= consts::LARGEST_SUNFLOWER_MULTIPLIER;
+            }
+        }
+        if entity.entity_type() == &EntityType::Cactus {
+            amount *= (region_size * region_size) as u64;
         }
+        if entity.entity_type() == &EntityType::Pumpkin {
+            amount *= giant_pumpkin_multiplier(region_size);
+        }
+        if entity.entity_type() == &EntityType::Treasure {
+            amount *= region_size as u64;
+        }
+        if self.has_companion_at(x, y)? {
+            amount *= consts::COMPANION_YIELD_MULTIPLIER;
+        }
+        Ok(Some((item, amount)))
+    }
+
+    pub fn can_harvest(&mut self) -> Result<bool, EngineError> {
+        self.spend(Operation::CanHarvest);
+        let x = self.drone().x();
+        let y = self.drone().y();
+        self.refresh_tile(x, y)?;
+        self.is_harvestable_at(x, y)
+    }
+
+    fn refresh_tile(&mut self, x: usize, y: usize) -> Result<(), EngineError> {
+        let now = self.clock.now();
+        self.world.mut_tile(x, y)?.refresh(now);
+        Ok(())
     }
 
-    pub fn get_entity_type(&self) -> Result<Option<&EntityType>, EngineError> {
-        let x = self.drone.x();
-        let y = self.drone.y();
+    fn is_harvestable_at(&self, x: usize, y: usize) -> Result<bool, EngineError> {
+        let tile = self.world.get_tile(x, y)?;
+        let yields = tile
+            .entity()
+            .is_some_and(|entity| entity.entity_type().harvest_yield().is_some());
+        Ok(yields && tile.is_grown(self.clock.now()))
+    }
+
+    pub fn get_entity_type(&mut self) -> Result<Option<&EntityType>, EngineError> {
+        self.spend(Operation::GetEntityType);
+        let x = self.drone().x();
+        let y = self.drone().y();
+        self.refresh_tile(x, y)?;
         let tile = self.world.get_tile(x, y)?;
         if let Some(entity) = tile.entity() {
             Ok(Some(entity.entity_type()))
@@ -80,70 +645,237 @@ impl Game {
         }
     }
 
-    pub fn get_ground_type(&self) -> Result<&GroundType, EngineError> {
-        let x = self.drone.x();
-        let y = self.drone.y();
+    pub fn get_ground_type(&mut self) -> Result<&GroundType, EngineError> {
+        self.spend(Operation::GetGroundType);
+        let x = self.drone().x();
+        let y = self.drone().y();
         let tile = self.world.get_tile(x, y)?;
         Ok(tile.ground_type())
     }
 
+    pub fn use_item(&mut self, item: Item) -> Result<(), EngineError> {
+        self.logged(Action::UseItem(item), |game| game.use_item_unlogged(item))
+    }
+
+    fn use_item_unlogged(&mut self, item: Item) -> Result<(), EngineError> {
+        self.spend(Operation::UseItem);
+        let x = self.drone().x();
+        let y = self.drone().y();
+        let now = self.clock.now();
+        match item {
+            Item::Water => {
+                self.require(Unlock::Watering)?;
+                self.inventory.remove(&[(Item::Water, 1)])?;
+                self.world
+                    .mut_tile(x, y)?
+                    .add_water(consts::WATER_PER_USE, now);
+                Ok(())
+            }
+            Item::Fertilizer => {
+                self.require(Unlock::Fertilizer)?;
+                let tile = self.world.mut_tile(x, y)?;
+                if tile.entity().is_none() {
+                    return Err(EngineError::EntityNotPlanted);
+                }
+                self.inventory.remove(&[(Item::Fertilizer, 1)])?;
+                tile.settle_growth(now);
+                let was_grown = tile.is_grown(now);
+                if let Some(entity) = tile.mut_entity() {
+                    entity.grow(Duration::from_secs_f64(consts::FERTILIZER_GROWTH_TIME), now);
+                }
+                if !was_grown && tile.is_grown(now) {
+                    if let Some(entity_type) =
+                        tile.entity().map(|entity| entity.entity_type().clone())
+                    {
+                        self.emit(|_| Event::EntityGrown {
+                            position: (x, y),
+                            entity_type,
+                        });
+                    }
+                }
+                Ok(())
+            }
+            Item::WeirdSubstance => {
+                self.require(Unlock::Mazes)?;
+                self.spawn_maze()
+            }
+            _ => Err(EngineError::ItemNotUsable(item)),
+        }
+    }
+
+    pub fn measure(&mut self) -> Result<Option<Measurement>, EngineError> {
+        self.spend(Operation::Measure);
+        self.require(Unlock::Measure)?;
+        if self.drone().hat() == Hat::Dinosaur {
+            return Ok(self
+                .apple_position()
+                .map(|(x, y)| Measurement::Position(x, y)));
+        }
+        let x = self.drone().x();
+        let y = self.drone().y();
+        let tile = self.world.get_tile(x, y)?;
+        Ok(tile
+            .entity()
+            .and_then(Entity::measure)
+            .map(Measurement::Size))
+    }
+
+    pub fn measure_dir(
+        &mut self,
+        direction: Direction,
+    ) -> Result<Option<Measurement>, EngineError> {
+        self.spend(Operation::Measure);
+        self.require(Unlock::Measure)?;
+        let (x, y) = self
+            .world
+            .neighbor(self.drone().x(), self.drone().y(), direction);
+        let tile = self.world.get_tile(x, y)?;
+        Ok(tile
+            .entity()
+            .and_then(Entity::measure)
+            .map(Measurement::Size))
+    }
+
+    pub fn get_companion(&mut self) -> Result<Option<&Companion>, EngineError> {
+        self.spend(Operation::GetCompanion);
+        self.require(Unlock::Polyculture)?;
+        let x = self.drone().x();
+        let y = self.drone().y();
+        let tile = self.world.get_tile(x, y)?;
+        Ok(tile.entity().and_then(Entity::companion))
+    }
+
+    pub fn swap(&mut self, direction: Direction) -> Result<(), EngineError> {
+        self.logged(Action::Swap(direction), |game| {
+            game.swap_unlogged(direction)
+        })
+    }
+
+    fn swap_unlogged(&mut self, direction: Direction) -> Result<(), EngineError> {
+        self.spend(Operation::Swap);
+        self.require(Unlock::Swap)?;
+        let now = self.clock.now();
+        let here = (self.drone().x(), self.drone().y());
+        let there = self.world.neighbor(here.0, here.1, direction);
+        self.world.mut_tile(here.0, here.1)?.settle_growth(now);
+        self.world.mut_tile(there.0, there.1)?.settle_growth(now);
+        self.world.swap_entities(here, there)?;
+        self.update_tree_growth_around(here.0, here.1)?;
+        self.update_tree_growth_around(there.0, there.1)
+    }
+
+    pub fn get_water(&mut self) -> Result<f64, EngineError> {
+        self.spend(Operation::GetWater);
+        let x = self.drone().x();
+        let y = self.drone().y();
+        let tile = self.world.get_tile(x, y)?;
+        Ok(tile.water(self.clock.now()))
+    }
+
     pub fn till(&mut self) -> Result<(), EngineError> {
-        let x = self.drone.x();
-        let y = self.drone.y();
+        self.logged(Action::Till, |game| game.till_unlogged())
+    }
+
+    fn till_unlogged(&mut self) -> Result<(), EngineError> {
+        self.spend(Operation::Till);
+        self.require(Unlock::Till)?;
+        let x = self.drone().x();
+        let y = self.drone().y();
         let tile = self.world.mut_tile(x, y)?;
         if tile.ground_type() == &GroundType::Grassland {
             tile.set_ground_type(GroundType::Soil);
         } else if tile.ground_type() == &GroundType::Soil {
             tile.set_ground_type(GroundType::Grassland);
         }
+        let ground_type = tile.ground_type().clone();
+        self.emit(|game| Event::Tilled {
+            drone: game.active_drone,
+            position: (x, y),
+            ground_type,
+        });
         Ok(())
     }
 
     pub fn move_drone(&mut self, direction: Direction) -> Result<(), EngineError> {
-        let width = self.world.width();
-        let height = self.world.height();
-
-        let (x, y) = match direction {
-            Direction::North => {
-                let new_y = if self.drone.y() == 0 {
-                    height - 1
-                } else {
-                    self.drone.y() - 1
-                };
-                (self.drone.x(), new_y)
-            }
-            Direction::East => {
-                let new_x = (self.drone.x() + 1) % width;
-                (new_x, self.drone.y())
-            }
-            Direction::South => {
-                let new_y = (self.drone.y() + 1) % height;
-                (self.drone.x(), new_y)
-            }
-            Direction::West => {
-                let new_x = if self.drone.x() == 0 {
-                    width - 1
-                } else {
-                    self.drone.x() - 1
-                };
-                (new_x, self.drone.y())
-            }
-        };
-        self.drone.set_position(x, y);
+        self.logged(Action::Move(direction), |game| {
+            game.move_drone_unlogged(direction)
+        })
+    }
+
+    fn move_drone_unlogged(&mut self, direction: Direction) -> Result<(), EngineError> {
+        self.spend(Operation::Move);
+        if self
+            .world
+            .has_wall(self.drone().x(), self.drone().y(), direction)
+        {
+            return Err(EngineError::BlockedByWall(direction));
+        }
+        let (x, y) = self
+            .world
+            .neighbor(self.drone().x(), self.drone().y(), direction);
+        let from = self.drone_position();
+        if self.drone().hat() == Hat::Dinosaur {
+            self.move_dinosaur(direction, x, y)?;
+        } else {
+            self.mut_drone().set_position(x, y);
+        }
+        self.emit(|game| Event::DroneMoved {
+            drone: game.active_drone,
+            from,
+            to: (x, y),
+        });
         Ok(())
     }
 
-    pub fn get_pos_x(&self) -> usize {
-        self.drone.x()
+    pub fn can_move(&mut self, direction: Direction) -> bool {
+        self.spend(Operation::CanMove);
+        let (x, y) = (self.drone().x(), self.drone().y());
+        if self.world.has_wall(x, y, direction) {
+            return false;
+        }
+        let (nx, ny) = self.world.neighbor(x, y, direction);
+        self.drone().hat() != Hat::Dinosaur || !self.tail_blocks(nx, ny)
     }
 
-    pub fn get_pos_y(&self) -> usize {
-        self.drone.y()
+    pub fn change_hat(&mut self, hat: Hat) -> Result<(), EngineError> {
+        self.logged(Action::ChangeHat(hat), |game| game.change_hat_unlogged(hat))
+    }
+
+    fn change_hat_unlogged(&mut self, hat: Hat) -> Result<(), EngineError> {
+        self.spend(Operation::ChangeHat);
+        if hat == Hat::Dinosaur {
+            self.require(Unlock::Dinosaur)?;
+        }
+        if hat == self.drone().hat() {
+            return Ok(());
+        }
+        match hat {
+            Hat::Dinosaur => self.put_on_dinosaur_hat(),
+            Hat::Straw => self.take_off_dinosaur_hat(),
+        }
+    }
+
+    pub fn get_pos_x(&mut self) -> usize {
+        self.spend(Operation::GetPosX);
+        self.drone().x()
+    }
+
+    pub fn get_pos_y(&mut self) -> usize {
+        self.spend(Operation::GetPosY);
+        self.drone().y()
     }
 
     pub fn clear(&mut self) -> Result<(), EngineError> {
-        self.drone.set_position(0, 0);
-        self.world.clear()?;
+        self.logged(Action::Clear, |game| game.clear_unlogged())
+    }
+
+    fn clear_unlogged(&mut self) -> Result<(), EngineError> {
+        for drone in &mut self.drones {
+            drone.set_position(0, 0).set_hat(Hat::Straw).clear_tail();
+        }
+        self.world.clear(self.clock.now())?;
+        self.update_all_tree_growth()?;
+        self.emit(|_| Event::WorldCleared);
         Ok(())
     }
 }
@@ -151,27 +883,72 @@ impl Game {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{game::testing::TestGame, world::generator::WorldGenerator};
 
     fn create_test_game() -> Game {
-        Game::new(GameOptions {
-            world_width: 5,
-            world_height: 5,
-        })
+        TestGame::new(5, 5)
+            .item(Item::Hay, 100)
+            .item(Item::Wood, 100)
+            .item(Item::Carrot, 100)
+            .build()
     }
 
     #[test]
     fn can_create_game_with_correct_initial_state() {
-        let game = create_test_game();
+        let mut game = create_test_game();
         assert_eq!(game.get_world_size(), (5, 5));
         assert_eq!(game.get_pos_x(), 0);
         assert_eq!(game.get_pos_y(), 0);
     }
 
+    #[test]
+    fn actions_advance_elapsed_ticks_by_their_cost() {
+        let mut game = create_test_game();
+        assert_eq!(game.elapsed_ticks(), 0);
+
+        game.move_drone(Direction::East).unwrap();
+        assert_eq!(game.elapsed_ticks(), consts::MOVE_TICKS);
+
+        game.till().unwrap();
+        game.plant(EntityType::Carrot).unwrap();
+        game.can_harvest().unwrap();
+        game.get_entity_type().unwrap();
+        assert_eq!(
+            game.elapsed_ticks(),
+            consts::MOVE_TICKS + consts::TILL_TICKS + consts::PLANT_TICKS + 2 * consts::QUERY_TICKS
+        );
+        assert_eq!(game.now(), ticks_to_duration(game.elapsed_ticks()));
+    }
+
+    #[test]
+    fn failed_actions_still_cost_ticks() {
+        let mut game = create_test_game();
+
+        assert!(game.harvest().is_err());
+        assert_eq!(game.elapsed_ticks(), consts::HARVEST_TICKS);
+    }
+
+    #[test]
+    fn can_configure_operation_costs() {
+        let mut operation_costs = OperationCosts::default();
+        operation_costs.set_ticks(Operation::Move, 1);
+        let mut game = Game::new(GameOptions {
+            world_width: 5,
+            world_height: 5,
+            operation_costs,
+            ..Default::default()
+        });
+
+        game.move_drone(Direction::South).unwrap();
+        assert_eq!(game.elapsed_ticks(), 1);
+    }
+
     #[test]
     fn can_get_world_dimensions() {
-        let game = Game::new(GameOptions {
+        let mut game = Game::new(GameOptions {
             world_width: 10,
             world_height: 15,
+            ..Default::default()
         });
         assert_eq!(game.get_world_size(), (10, 15));
     }
@@ -221,6 +998,51 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn planting_consumes_resources() {
+        let mut game = create_test_game();
+
+        game.till().unwrap();
+        game.plant(EntityType::Carrot).unwrap();
+        assert_eq!(game.num_items(Item::Hay), 100 - consts::CARROT_HAY_COST);
+        assert_eq!(game.num_items(Item::Wood), 100 - consts::CARROT_WOOD_COST);
+
+        game.move_drone(Direction::East).unwrap();
+        game.till().unwrap();
+        game.plant(EntityType::Pumpkin).unwrap();
+        assert_eq!(
+            game.num_items(Item::Carrot),
+            100 - consts::PUMPKIN_CARROT_COST
+        );
+    }
+
+    #[test]
+    fn cannot_plant_without_resources() {
+        let mut game = TestGame::new(5, 5).item(Item::Hay, 1).build();
+
+        game.till().unwrap();
+        let result = game.plant(EntityType::Carrot);
+        assert!(matches!(
+            result.unwrap_err(),
+            EngineError::InsufficientResources {
+                item: Item::Wood,
+                needed: 1,
+                have: 0
+            }
+        ));
+        assert!(game.get_entity_type().unwrap().is_none());
+        assert_eq!(game.num_items(Item::Hay), 1);
+    }
+
+    #[test]
+    fn rejected_planting_does_not_consume_resources() {
+        let mut game = create_test_game();
+
+        assert!(game.plant(EntityType::Carrot).is_err());
+        assert_eq!(game.num_items(Item::Hay), 100);
+        assert_eq!(game.num_items(Item::Wood), 100);
+    }
+
     #[test]
     fn cannot_plant_grass_on_soil() {
         let mut game = create_test_game();
@@ -240,15 +1062,495 @@ mod tests {
 
         game.plant(EntityType::Grass).unwrap();
         assert!(game.get_entity_type().unwrap().is_some());
+        assert!(!game.can_harvest().unwrap());
+
+        game.advance_time(EntityType::Grass.growth_time());
+        assert!(game.can_harvest().unwrap());
+
+        game.harvest().unwrap();
+        assert!(game.get_entity_type().unwrap().is_none());
+    }
+
+    #[test]
+    fn harvesting_credits_inventory() {
+        let mut game = create_test_game();
+
+        let wood = game.num_items(Item::Wood);
+        game.plant(EntityType::Tree).unwrap();
+        game.advance_time(EntityType::Tree.growth_time());
+        game.harvest().unwrap();
+        assert_eq!(game.num_items(Item::Wood), wood + consts::TREE_WOOD_YIELD);
+
+        game.till().unwrap();
+        game.plant(EntityType::Pumpkin).unwrap();
+        game.advance_time(EntityType::Pumpkin.growth_time());
+        let pumpkins = game.num_items(Item::Pumpkin);
+        game.harvest().unwrap();
+        assert_eq!(
+            game.num_items(Item::Pumpkin),
+            pumpkins + consts::PUMPKIN_YIELD
+        );
+    }
+
+    #[test]
+    fn harvesting_ungrown_entity_yields_nothing() {
+        let mut game = create_test_game();
+
+        let hay = game.num_items(Item::Hay);
+        game.plant(EntityType::Grass).unwrap();
+        assert!(game.harvest().is_err());
+        assert_eq!(game.num_items(Item::Hay), hay);
+    }
+
+    #[test]
+    fn watering_raises_tile_water_and_consumes_item() {
+        let mut game = create_test_game();
+        game.mut_inventory().add(Item::Water, 2);
+
+        assert_eq!(game.get_water().unwrap(), 0.0);
+
+        game.use_item(Item::Water).unwrap();
+        assert!(game.get_water().unwrap() > 0.0);
+        assert!(game.get_water().unwrap() <= consts::WATER_PER_USE);
+        assert_eq!(game.num_items(Item::Water), 1);
+    }
+
+    #[test]
+    fn cannot_water_without_water_items() {
+        let mut game = create_test_game();
 
-        let tile = game.world.get_tile(0, 0).unwrap();
-        let entity = tile.entity().unwrap();
-        let now = chrono::Local::now().time();
-        assert!(!entity.is_grown_at(now));
+        let result = game.use_item(Item::Water);
+        assert!(matches!(
+            result.unwrap_err(),
+            EngineError::InsufficientResources {
+                item: Item::Water,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn cannot_use_non_usable_item() {
+        let mut game = create_test_game();
+
+        let result = game.use_item(Item::Hay);
+        assert!(matches!(
+            result.unwrap_err(),
+            EngineError::ItemNotUsable(Item::Hay)
+        ));
+    }
+
+    #[test]
+    fn watered_entities_grow_faster() {
+        let mut game = create_test_game();
+        game.mut_inventory().add(Item::Water, 4);
+
+        game.plant(EntityType::Tree).unwrap();
+        for _ in 0..4 {
+            game.use_item(Item::Water).unwrap();
+        }
+        game.advance_time(EntityType::Tree.growth_time() / 2);
+        assert!(game.can_harvest().unwrap());
+
+        game.move_drone(Direction::East).unwrap();
+        game.plant(EntityType::Tree).unwrap();
+        game.advance_time(EntityType::Tree.growth_time() / 2);
         assert!(!game.can_harvest().unwrap());
+    }
 
-        let grown_time = now + entity.entity_type().growth_time();
-        assert!(entity.is_grown_at(grown_time));
+    #[test]
+    fn fertilizer_fast_forwards_growth() {
+        let mut game = create_test_game();
+        game.mut_inventory().add(Item::Fertilizer, 2);
+
+        game.plant(EntityType::Tree).unwrap();
+        game.use_item(Item::Fertilizer).unwrap();
+        game.use_item(Item::Fertilizer).unwrap();
+        assert!(!game.can_harvest().unwrap());
+
+        game.advance_time(Duration::from_secs(1));
+        assert!(game.can_harvest().unwrap());
+        assert_eq!(game.num_items(Item::Fertilizer), 0);
+    }
+
+    #[test]
+    fn cannot_fertilize_empty_tile() {
+        let mut game = create_test_game();
+        game.mut_inventory().add(Item::Fertilizer, 1);
+
+        let result = game.use_item(Item::Fertilizer);
+        assert!(matches!(result.unwrap_err(), EngineError::EntityNotPlanted));
+        assert_eq!(game.num_items(Item::Fertilizer), 1);
+    }
+
+    #[test]
+    fn cannot_fertilize_without_fertilizer() {
+        let mut game = create_test_game();
+
+        game.plant(EntityType::Bush).unwrap();
+        let result = game.use_item(Item::Fertilizer);
+        assert!(matches!(
+            result.unwrap_err(),
+            EngineError::InsufficientResources {
+                item: Item::Fertilizer,
+                ..
+            }
+        ));
+    }
+
+    fn plant_sunflowers(game: &mut Game, count: usize) -> Vec<u32> {
+        (0..count)
+            .map(|_| {
+                game.till().unwrap();
+                game.plant(EntityType::Sunflower).unwrap();
+                let Some(Measurement::Size(petals)) = game.measure().unwrap() else {
+                    panic!("sunflower has no petal count");
+                };
+                game.move_drone(Direction::East).unwrap();
+                petals
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sunflowers_have_random_petal_counts() {
+        let mut game = create_test_game();
+
+        let petals = plant_sunflowers(&mut game, 5);
+        assert!(petals.iter().all(|petals| {
+            (consts::SUNFLOWER_MIN_PETALS..=consts::SUNFLOWER_MAX_PETALS).contains(petals)
+        }));
+        assert!(petals.iter().any(|&count| count != petals[0]));
+    }
+
+    #[test]
+    fn cannot_plant_sunflower_on_grassland() {
+        let mut game = create_test_game();
+
+        let result = game.plant(EntityType::Sunflower);
+        assert!(matches!(
+            result.unwrap_err(),
+            EngineError::EntityNotAllowedOnGroundType(EntityType::Sunflower, GroundType::Grassland)
+        ));
+    }
+
+    #[test]
+    fn measure_is_none_for_entities_without_size() {
+        let mut game = create_test_game();
+
+        assert_eq!(game.measure().unwrap(), None);
+        game.plant(EntityType::Bush).unwrap();
+        assert_eq!(game.measure().unwrap(), None);
+    }
+
+    #[test]
+    fn harvesting_largest_sunflower_yields_bonus_power() {
+        let mut game = create_test_game();
+        let petals = plant_sunflowers(&mut game, 5);
+        game.advance_time(EntityType::Sunflower.growth_time());
+
+        let largest = petals.iter().max().unwrap();
+        let smaller_x = petals.iter().position(|p| p < largest).unwrap();
+        let largest_x = petals.iter().position(|p| p == largest).unwrap();
+
+        while game.drone_position().0 != smaller_x {
+            game.move_drone(Direction::East).unwrap();
+        }
+        game.harvest().unwrap();
+        assert_eq!(
+            game.inventory().count(Item::Power),
+            consts::SUNFLOWER_POWER_YIELD
+        );
+
+        while game.drone_position().0 != largest_x {
+            game.move_drone(Direction::East).unwrap();
+        }
+        game.harvest().unwrap();
+        assert_eq!(
+            game.inventory().count(Item::Power),
+            consts::SUNFLOWER_POWER_YIELD
+                + consts::SUNFLOWER_POWER_YIELD * consts::LARGEST_SUNFLOWER_MULTIPLIER
+        );
+    }
+
+    #[test]
+    fn power_speeds_up_operations_and_drains() {
+        let mut game = create_test_game();
+        game.mut_inventory().add(Item::Power, 1);
+
+        game.move_drone(Direction::East).unwrap();
+        assert_eq!(
+            game.elapsed_ticks(),
+            consts::MOVE_TICKS / consts::POWER_SPEEDUP
+        );
+
+        for _ in 1..consts::POWER_OPERATIONS_PER_UNIT {
+            game.move_drone(Direction::East).unwrap();
+        }
+        assert_eq!(game.inventory().count(Item::Power), 0);
+
+        let ticks = game.elapsed_ticks();
+        game.move_drone(Direction::East).unwrap();
+        assert_eq!(game.elapsed_ticks(), ticks + consts::MOVE_TICKS);
+    }
+
+    fn create_locked_game() -> Game {
+        TestGame::new(5, 5)
+            .locked()
+            .item(Item::Hay, 100)
+            .item(Item::Wood, 100)
+            .item(Item::Water, 1)
+            .build()
+    }
+
+    #[test]
+    fn gated_actions_fail_until_unlocked() {
+        let mut game = create_locked_game();
+
+        assert!(matches!(
+            game.till().unwrap_err(),
+            EngineError::NotUnlocked(Unlock::Till)
+        ));
+        assert!(matches!(
+            game.plant(EntityType::Tree).unwrap_err(),
+            EngineError::NotUnlocked(Unlock::Trees)
+        ));
+        assert!(matches!(
+            game.use_item(Item::Water).unwrap_err(),
+            EngineError::NotUnlocked(Unlock::Watering)
+        ));
+        assert!(matches!(
+            game.swap(Direction::East).unwrap_err(),
+            EngineError::NotUnlocked(Unlock::Swap)
+        ));
+        assert!(matches!(
+            game.measure().unwrap_err(),
+            EngineError::NotUnlocked(Unlock::Measure)
+        ));
+        game.plant(EntityType::Bush).unwrap();
+    }
+
+    #[test]
+    fn unlocking_pays_from_inventory_and_opens_gate() {
+        let mut game = create_locked_game();
+
+        game.unlock(Unlock::Till).unwrap();
+        assert_eq!(game.num_unlocked(Unlock::Till), 1);
+        assert_eq!(
+            game.inventory().count(Item::Hay),
+            100 - consts::TILL_UNLOCK_HAY_COST
+        );
+        game.till().unwrap();
+        assert_eq!(game.get_ground_type().unwrap(), &GroundType::Soil);
+    }
+
+    #[test]
+    fn cannot_unlock_without_resources_or_twice() {
+        let mut game = create_locked_game();
+
+        assert!(matches!(
+            game.unlock(Unlock::Pumpkins).unwrap_err(),
+            EngineError::InsufficientResources {
+                item: Item::Carrot,
+                ..
+            }
+        ));
+        assert_eq!(game.num_unlocked(Unlock::Pumpkins), 0);
+
+        game.unlock(Unlock::Till).unwrap();
+        assert!(matches!(
+            game.unlock(Unlock::Till).unwrap_err(),
+            EngineError::FullyUnlocked(Unlock::Till)
+        ));
+    }
+
+    #[test]
+    fn game_can_move_between_threads() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Game>();
+
+        let mut game = create_test_game();
+        game.subscribe(|_: &Event| {});
+        let mut game = std::thread::spawn(move || {
+            game.till().unwrap();
+            game
+        })
+        .join()
+        .unwrap();
+        assert_eq!(game.get_ground_type().unwrap(), &GroundType::Soil);
+    }
+
+    #[test]
+    fn expansions_grow_the_world() {
+        let mut game = TestGame::new(3, 3).locked().item(Item::Wood, 1000).build();
+        game.plant(EntityType::Bush).unwrap();
+
+        game.unlock(Unlock::Expand).unwrap();
+        assert_eq!(game.get_world_size(), (4, 4));
+        game.unlock(Unlock::Expand).unwrap();
+        assert_eq!(game.get_world_size(), (6, 6));
+        assert_eq!(game.get_entity_type().unwrap(), Some(&EntityType::Bush));
+    }
+
+    #[test]
+    fn expansions_skip_sizes_the_world_already_has() {
+        let mut game = Game::new(GameOptions::default());
+        game.mut_inventory().add(Item::Wood, 10_000);
+        let skipped = consts::EXPAND_WORLD_SIZES
+            .iter()
+            .take_while(|&&size| size <= consts::DEFAULT_WORLD_SIZE)
+            .count() as u32;
+
+        game.unlock(Unlock::Expand).unwrap();
+        let size = consts::EXPAND_WORLD_SIZES[skipped as usize];
+        assert_eq!(game.get_world_size(), (size, size));
+        assert_eq!(game.unlocks().level(Unlock::Expand), skipped + 1);
+        assert_eq!(
+            game.inventory().count(Item::Wood),
+            10_000 - Unlock::Expand.cost(skipped)[0].1
+        );
+    }
+
+    #[test]
+    fn cannot_buy_expansion_that_does_not_grow_the_world() {
+        let largest = *consts::EXPAND_WORLD_SIZES.last().unwrap();
+        let mut game = TestGame::new(largest, largest + 1)
+            .item(Item::Wood, 100_000)
+            .build();
+
+        assert!(matches!(
+            game.unlock(Unlock::Expand),
+            Err(EngineError::WorldAlreadyExpanded(size)) if size == largest
+        ));
+        assert_eq!(game.inventory().count(Item::Wood), 100_000);
+        assert_eq!(game.unlocks().level(Unlock::Expand), 0);
+    }
+
+    #[test]
+    fn resizing_keeps_walls_on_wrapping_edges() {
+        let mut game = create_test_game();
+        game.world.set_wall(4, 1, Direction::East, true);
+        game.world.set_wall(1, 4, Direction::South, true);
+        game.world.set_wall(1, 1, Direction::East, true);
+
+        game.resize_world(6, 6).unwrap();
+        let world = game.world();
+        assert!(world.has_wall(5, 1, Direction::East));
+        assert!(world.has_wall(1, 5, Direction::South));
+        assert!(!world.has_wall(4, 1, Direction::East));
+        assert!(!world.has_wall(1, 4, Direction::South));
+        assert!(world.has_wall(1, 1, Direction::East));
+
+        game.world.set_wall(1, 0, Direction::East, true);
+        game.resize_world(2, 2).unwrap();
+        let world = game.world();
+        assert!(world.has_wall(1, 1, Direction::East));
+        assert!(world.has_wall(1, 1, Direction::South));
+        assert!(!world.has_wall(1, 0, Direction::East));
+    }
+
+    #[test]
+    fn resizing_keeps_drone_inside_world() {
+        let mut game = create_test_game();
+        game.move_drone(Direction::West).unwrap();
+        game.move_drone(Direction::North).unwrap();
+        assert_eq!(game.drone_position(), (4, 4));
+
+        game.resize_world(3, 2).unwrap();
+        assert_eq!(game.drone_position(), (2, 1));
+        game.move_drone(Direction::East).unwrap();
+        assert_eq!(game.drone_position(), (0, 1));
+    }
+
+    #[test]
+    fn can_start_on_generated_world() {
+        let world = WorldGenerator::new(2)
+            .size(6, 4)
+            .planted_ratio(1.0)
+            .generate()
+            .unwrap();
+        let mut game = Game::with_world(GameOptions::default(), world);
+
+        assert_eq!(game.get_world_size(), (6, 4));
+        assert!(game.get_entity_type().unwrap().is_some());
+    }
+
+    #[test]
+    fn can_spawn_drones_up_to_the_cap() {
+        let mut game = Game::new(GameOptions {
+            world_width: 5,
+            world_height: 5,
+            max_drones: 2,
+            ..Default::default()
+        });
+        game.move_drone(Direction::East).unwrap();
+
+        let drone = game.spawn_drone().unwrap();
+        assert_ne!(drone, game.active_drone());
+        assert_eq!(game.drone_ids().count(), 2);
+        assert_eq!(game.drone_positions(), vec![(1, 0), (1, 0)]);
+        assert!(matches!(
+            game.spawn_drone().unwrap_err(),
+            EngineError::DroneLimitReached(2)
+        ));
+    }
+
+    #[test]
+    fn actions_apply_to_the_selected_drone() {
+        let mut game = create_test_game();
+        let first = game.active_drone();
+        let second = game.spawn_drone().unwrap();
+
+        game.select_drone(second).unwrap();
+        game.move_drone(Direction::South).unwrap();
+        game.plant(EntityType::Tree).unwrap();
+        assert_eq!(game.get_pos_y(), 1);
+
+        game.select_drone(first).unwrap();
+        assert_eq!(game.get_pos_y(), 0);
+        assert_eq!(game.get_entity_type().unwrap(), None);
+        assert_eq!(game.drone_positions(), vec![(0, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn with_drone_restores_active_drone() {
+        let mut game = create_test_game();
+        let first = game.active_drone();
+        let second = game.spawn_drone().unwrap();
+
+        let position = game
+            .with_drone(second, |game| {
+                game.move_drone(Direction::East).unwrap();
+                game.drone_position()
+            })
+            .unwrap();
+        assert_eq!(position, (1, 0));
+        assert_eq!(game.active_drone(), first);
+        assert_eq!(game.drone_position(), (0, 0));
+    }
+
+    #[test]
+    fn cannot_select_unknown_drone() {
+        let mut game = create_test_game();
+        let drone = game.spawn_drone().unwrap();
+        let mut other = create_test_game();
+
+        assert!(matches!(
+            other.select_drone(drone).unwrap_err(),
+            EngineError::UnknownDrone(unknown) if unknown == drone
+        ));
+    }
+
+    #[test]
+    fn speed_upgrades_shorten_operations() {
+        let mut game = create_locked_game();
+        game.unlock(Unlock::Speed).unwrap();
+        game.unlock(Unlock::Speed).unwrap();
+        assert_eq!(game.num_unlocked(Unlock::Speed), 2);
+
+        let ticks = game.elapsed_ticks();
+        game.move_drone(Direction::East).unwrap();
+        assert_eq!(game.elapsed_ticks(), ticks + consts::MOVE_TICKS.div_ceil(3));
     }
 
     #[test]
@@ -274,7 +1576,7 @@ mod tests {
 
     #[test]
     fn can_get_ground_type() {
-        let game = create_test_game();
+        let mut game = create_test_game();
 
         let ground_type = game.get_ground_type().unwrap();
         assert_eq!(ground_type, &GroundType::Grassland);
@@ -450,11 +1752,8 @@ mod tests {
 
         assert!(!game.can_harvest().unwrap());
 
-        let tile = game.world.get_tile(0, 0).unwrap();
-        let entity = tile.entity().unwrap();
-        let now = chrono::Local::now().time();
-        let grown_time = now + entity.entity_type().growth_time();
-        assert!(entity.is_grown_at(grown_time));
+        game.advance_time(EntityType::Grass.growth_time());
+        assert!(game.can_harvest().unwrap());
     }
 
     #[test]
@@ -465,11 +1764,77 @@ mod tests {
 
         assert!(!game.can_harvest().unwrap());
 
-        let tile = game.world.get_tile(0, 0).unwrap();
-        let entity = tile.entity().unwrap();
-        let now = chrono::Local::now().time();
-        let grown_time = now + entity.entity_type().growth_time();
-        assert!(entity.is_grown_at(grown_time));
+        game.advance_time(EntityType::Tree.growth_time() - Duration::from_millis(10));
+        assert!(!game.can_harvest().unwrap());
+
+        game.advance_time(Duration::from_millis(10));
+        assert!(game.can_harvest().unwrap());
+    }
+
+    #[test]
+    fn growth_spanning_midnight_is_not_harvestable_early() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let mut game = Game::with_clock(
+            GameOptions {
+                world_width: 5,
+                world_height: 5,
+                growth_variance: 0.0,
+                ..Default::default()
+            },
+            SimulatedClock::starting_at(day - Duration::from_secs(2)),
+        );
+
+        game.plant(EntityType::Tree).unwrap();
+        game.advance_time(Duration::from_secs(2));
+        assert!(!game.can_harvest().unwrap());
+
+        game.advance_time(EntityType::Tree.growth_time());
+        assert!(game.can_harvest().unwrap());
+    }
+
+    fn planted_growth_times(seed: u64, growth_variance: f64) -> Vec<Duration> {
+        let mut game = TestGame::new(5, 5)
+            .seed(seed)
+            .growth_variance(growth_variance)
+            .build();
+        (0..5)
+            .map(|_| {
+                game.plant(EntityType::Tree).unwrap();
+                let (x, y) = game.drone_position();
+                let entity = game.world().get_tile(x, y).unwrap().entity().unwrap();
+                let growth_time = entity.growth_time();
+                // Plant diagonally so that no two trees are adjacent.
+                game.move_drone(Direction::East).unwrap();
+                game.move_drone(Direction::South).unwrap();
+                growth_time
+            })
+            .collect()
+    }
+
+    #[test]
+    fn growth_time_is_jittered_within_bounds() {
+        let base = EntityType::Tree.growth_time();
+        let growth_times = planted_growth_times(7, 0.2);
+
+        assert!(growth_times.iter().any(|&growth_time| growth_time != base));
+        for growth_time in growth_times {
+            assert!(growth_time >= base.mul_f64(0.8));
+            assert!(growth_time <= base.mul_f64(1.2));
+        }
+    }
+
+    #[test]
+    fn growth_jitter_is_reproducible_from_seed() {
+        assert_eq!(planted_growth_times(42, 0.2), planted_growth_times(42, 0.2));
+        assert_ne!(planted_growth_times(42, 0.2), planted_growth_times(43, 0.2));
+    }
+
+    #[test]
+    fn zero_variance_uses_base_growth_time() {
+        let base = EntityType::Tree.growth_time();
+        assert!(planted_growth_times(7, 0.0)
+            .into_iter()
+            .all(|growth_time| growth_time == base));
     }
 
     #[test]
@@ -0,0 +1,97 @@
+use crate::{
+    error::EngineError,
+    game::{inventory::Item, unlock::Unlock, Direction, DroneId, Game, Hat},
+    world::entities::EntityType,
+};
+
+/// A drone action that can be queued and performed later.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    Move(Direction),
+    Till,
+    Plant(EntityType),
+    Harvest,
+    UseItem(Item),
+    Swap(Direction),
+    ChangeHat(Hat),
+    SpawnDrone,
+    SelectDrone(DroneId),
+    Unlock(Unlock),
+    Clear,
+    Resize(usize, usize),
+    AddItem(Item, u64),
+}
+
+impl Action {
+    /// Performs the action with the active drone.
+    pub fn apply(&self, game: &mut Game) -> Result<(), EngineError> {
+        match self {
+            Action::Move(direction) => game.move_drone(*direction),
+            Action::Till => game.till(),
+            Action::Plant(entity_type) => game.plant(entity_type.clone()),
+            Action::Harvest => game.harvest(),
+            Action::UseItem(item) => game.use_item(*item),
+            Action::Swap(direction) => game.swap(*direction),
+            Action::ChangeHat(hat) => game.change_hat(*hat),
+            Action::SpawnDrone => game.spawn_drone().map(|_| ()),
+            Action::SelectDrone(drone) => game.select_drone(*drone),
+            Action::Unlock(unlock) => game.unlock(*unlock),
+            Action::Clear => game.clear(),
+            Action::Resize(width, height) => game.resize_world(*width, *height),
+            Action::AddItem(item, amount) => {
+                game.add_item(*item, *amount);
+                Ok(())
+            }
+        }
+    }
+
+    /// Tiles the action changes when performed by the active drone.
+    pub fn target_tiles(&self, game: &Game) -> Vec<(usize, usize)> {
+        let (x, y) = game.drone_position();
+        match self {
+            Action::Move(_)
+            | Action::ChangeHat(_)
+            | Action::SpawnDrone
+            | Action::SelectDrone(_)
+            | Action::Unlock(_)
+            | Action::AddItem(..) => Vec::new(),
+            Action::Till | Action::Plant(_) | Action::Harvest | Action::UseItem(_) => {
+                vec![(x, y)]
+            }
+            Action::Swap(direction) => vec![(x, y), game.world().neighbor(x, y, *direction)],
+            Action::Clear | Action::Resize(..) => {
+                let (width, height) = (game.world().width(), game.world().height());
+                (0..width)
+                    .flat_map(|x| (0..height).map(move |y| (x, y)))
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{consts, game::GameOptions};
+
+    #[test]
+    fn applying_action_performs_it() {
+        let mut game = Game::new(GameOptions::default());
+
+        Action::Move(Direction::East).apply(&mut game).unwrap();
+        Action::Plant(EntityType::Bush).apply(&mut game).unwrap();
+        assert_eq!(game.drone_position(), (1, 0));
+        assert_eq!(game.get_entity_type().unwrap(), Some(&EntityType::Bush));
+    }
+
+    #[test]
+    fn swap_targets_both_tiles() {
+        let game = Game::new(GameOptions::default());
+        assert_eq!(
+            Action::Swap(Direction::West).target_tiles(&game),
+            vec![(0, 0), (consts::DEFAULT_WORLD_SIZE - 1, 0)]
+        );
+        assert!(Action::Move(Direction::West).target_tiles(&game).is_empty());
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::{
+    error::EngineError,
+    game::{Direction, Game},
+    world::entities::{Entity, EntityType},
+};
+
+impl Game {
+    fn cactus_size_at(&self, x: usize, y: usize) -> Result<Option<u32>, EngineError> {
+        let entity = self.world.get_tile(x, y)?.entity();
+        Ok(entity
+            .filter(|entity| entity.entity_type() == &EntityType::Cactus)
+            .and_then(Entity::measure))
+    }
+
+    /// A cactus is sorted when no cactus to its North or East is smaller and
+    /// no cactus to its South or West is larger.
+    fn is_sorted_cactus(&self, x: usize, y: usize) -> Result<bool, EngineError> {
+        let Some(size) = self.cactus_size_at(x, y)? else {
+            return Ok(false);
+        };
+        for direction in Direction::ALL {
+            let Some((nx, ny)) = self.world.bounded_neighbor(x, y, direction) else {
+                continue;
+            };
+            let Some(neighbor) = self.cactus_size_at(nx, ny)? else {
+                continue;
+            };
+            let sorted = match direction {
+                Direction::North | Direction::East => neighbor >= size,
+                Direction::South | Direction::West => neighbor <= size,
+            };
+            if !sorted {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Connected grown, sorted cacti harvested together with the cactus at
+    /// `(x, y)`. An unsorted cactus is harvested on its own.
+    pub(super) fn sorted_cactus_region(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> Result<Vec<(usize, usize)>, EngineError> {
+        if !self.is_sorted_cactus(x, y)? {
+            return Ok(vec![(x, y)]);
+        }
+
+        let now = self.clock.now();
+        let mut region = vec![(x, y)];
+        let mut visited = HashSet::from([(x, y)]);
+        let mut queue = VecDeque::from([(x, y)]);
+        while let Some((cx, cy)) = queue.pop_front() {
+            for direction in Direction::ALL {
+                let Some(next) = self.world.bounded_neighbor(cx, cy, direction) else {
+                    continue;
+                };
+                if !visited.insert(next) {
+                    continue;
+                }
+                if self.world.get_tile(next.0, next.1)?.is_grown(now)
+                    && self.is_sorted_cactus(next.0, next.1)?
+                {
+                    region.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        Ok(region)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        consts,
+        game::{inventory::Item, testing::TestGame, Direction, Game, Measurement},
+        world::{
+            entities::{Entity, EntityType},
+            tiles::GroundType,
+        },
+    };
+
+    fn create_cactus_game(sizes: &[u32]) -> Game {
+        let mut game = TestGame::new(sizes.len(), 1).build();
+        for (x, &size) in sizes.iter().enumerate() {
+            let mut entity = Entity::new(EntityType::Cactus, game.now());
+            entity.set_measure(Some(size));
+            let tile = game.world.mut_tile(x, 0).unwrap();
+            tile.set_ground_type(GroundType::Soil);
+            tile.set_entity(Some(entity)).unwrap();
+        }
+        game.advance_time(EntityType::Cactus.growth_time());
+        game
+    }
+
+    #[test]
+    fn planted_cacti_have_random_sizes() {
+        let mut game = TestGame::new(5, 1).build();
+        let mut sizes = Vec::new();
+        for _ in 0..5 {
+            game.till().unwrap();
+            game.plant(EntityType::Cactus).unwrap();
+            let Some(Measurement::Size(size)) = game.measure().unwrap() else {
+                panic!("cactus has no size");
+            };
+            sizes.push(size);
+            game.move_drone(Direction::East).unwrap();
+        }
+
+        assert!(sizes
+            .iter()
+            .all(|size| (consts::CACTUS_MIN_SIZE..=consts::CACTUS_MAX_SIZE).contains(size)));
+        assert!(sizes.iter().any(|&size| size != sizes[0]));
+    }
+
+    #[test]
+    fn can_measure_neighbor_in_direction() {
+        let mut game = create_cactus_game(&[3, 5, 7]);
+
+        assert_eq!(
+            game.measure_dir(Direction::East).unwrap(),
+            Some(Measurement::Size(5))
+        );
+        assert_eq!(
+            game.measure_dir(Direction::West).unwrap(),
+            Some(Measurement::Size(7))
+        );
+    }
+
+    #[test]
+    fn swap_exchanges_entities_with_neighbor() {
+        let mut game = create_cactus_game(&[5, 3]);
+
+        game.swap(Direction::East).unwrap();
+        assert_eq!(game.measure().unwrap(), Some(Measurement::Size(3)));
+        assert_eq!(
+            game.measure_dir(Direction::East).unwrap(),
+            Some(Measurement::Size(5))
+        );
+    }
+
+    #[test]
+    fn harvesting_sorted_row_chain_harvests_with_squared_yield() {
+        let mut game = create_cactus_game(&[1, 2, 2, 4]);
+
+        game.harvest().unwrap();
+        assert_eq!(
+            game.inventory().count(Item::Cactus),
+            16 * consts::CACTUS_YIELD
+        );
+        for x in 0..4 {
+            assert!(game.world().get_tile(x, 0).unwrap().entity().is_none());
+        }
+    }
+
+    #[test]
+    fn unsorted_cacti_are_excluded_from_chain() {
+        let mut game = create_cactus_game(&[1, 2, 9, 4]);
+
+        game.harvest().unwrap();
+        assert_eq!(
+            game.inventory().count(Item::Cactus),
+            4 * consts::CACTUS_YIELD
+        );
+        assert!(game.world().get_tile(2, 0).unwrap().entity().is_some());
+        assert!(game.world().get_tile(3, 0).unwrap().entity().is_some());
+    }
+
+    #[test]
+    fn unsorted_cactus_is_harvested_alone() {
+        let mut game = create_cactus_game(&[5, 2, 3]);
+
+        game.harvest().unwrap();
+        assert_eq!(game.inventory().count(Item::Cactus), consts::CACTUS_YIELD);
+        assert!(game.world().get_tile(1, 0).unwrap().entity().is_some());
+    }
+}
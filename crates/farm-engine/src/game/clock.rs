@@ -0,0 +1,112 @@
+use std::time::{Duration, Instant};
+
+/// Source of engine time, measured as the duration since the start of the run.
+pub trait Clock: Send {
+    fn now(&self) -> Duration;
+    fn advance(&mut self, duration: Duration);
+    /// Jumps to `now`, which may be earlier than the current time.
+    fn set_now(&mut self, now: Duration);
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedClock {
+    now: Duration,
+}
+
+impl SimulatedClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn starting_at(now: Duration) -> Self {
+        Self { now }
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> Duration {
+        self.now
+    }
+
+    fn advance(&mut self, duration: Duration) {
+        self.now += duration;
+    }
+
+    fn set_now(&mut self, now: Duration) {
+        self.now = now;
+    }
+}
+
+/// Follows the wall clock from the moment it is created. Time passes on its
+/// own, so `advance` is a no-op.
+#[derive(Debug, Clone)]
+pub struct RealClock {
+    started: Instant,
+}
+
+impl RealClock {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Default for RealClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for RealClock {
+    fn now(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    fn advance(&mut self, _duration: Duration) {}
+
+    fn set_now(&mut self, now: Duration) {
+        if let Some(started) = Instant::now().checked_sub(now) {
+            self.started = started;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulated_clock_starts_at_zero() {
+        let clock = SimulatedClock::new();
+        assert_eq!(clock.now(), Duration::ZERO);
+    }
+
+    #[test]
+    fn simulated_clock_only_moves_when_advanced() {
+        let mut clock = SimulatedClock::new();
+        let start = clock.now();
+        assert_eq!(clock.now(), start);
+
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(clock.now(), start + Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn real_clock_is_monotonic() {
+        let clock = RealClock::new();
+        let first = clock.now();
+        assert!(clock.now() >= first);
+    }
+
+    #[test]
+    fn can_set_clocks_back() {
+        let mut clock = SimulatedClock::starting_at(Duration::from_secs(5));
+        clock.set_now(Duration::from_secs(2));
+        assert_eq!(clock.now(), Duration::from_secs(2));
+
+        let mut clock = RealClock::new();
+        clock.set_now(Duration::from_secs(2));
+        assert!(clock.now() >= Duration::from_secs(2));
+    }
+}
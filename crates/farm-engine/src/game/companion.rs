@@ -0,0 +1,203 @@
+use rand::Rng;
+
+use crate::{
+    consts,
+    error::EngineError,
+    game::{unlock::Unlock, Game},
+    world::entities::{Companion, EntityType},
+};
+
+const COMPANION_TYPES: [EntityType; 4] = [
+    EntityType::Grass,
+    EntityType::Bush,
+    EntityType::Tree,
+    EntityType::Carrot,
+];
+
+impl Game {
+    /// Picks a companion of another polyculture type within
+    /// `COMPANION_MAX_DISTANCE` steps of `(x, y)`.
+    pub(super) fn roll_companion(
+        &mut self,
+        entity_type: &EntityType,
+        x: usize,
+        y: usize,
+    ) -> Option<Companion> {
+        if !entity_type.is_polyculture() || !self.unlocks.is_unlocked(Unlock::Polyculture) {
+            return None;
+        }
+
+        let max = consts::COMPANION_MAX_DISTANCE as isize;
+        let mut positions = Vec::new();
+        for dx in -max..=max {
+            let reach = max - dx.abs();
+            for dy in -reach..=reach {
+                let position = self.world.offset(x, y, dx, dy);
+                if position != (x, y) && !positions.contains(&position) {
+                    positions.push(position);
+                }
+            }
+        }
+        let types: Vec<_> = COMPANION_TYPES
+            .iter()
+            .filter(|companion_type| *companion_type != entity_type)
+            .collect();
+        if positions.is_empty() {
+            return None;
+        }
+
+        let companion_type = types[self.rng.random_range(0..types.len())].clone();
+        let position = positions[self.rng.random_range(0..positions.len())];
+        Some(Companion::new(companion_type, position))
+    }
+
+    /// Whether the entity at `(x, y)` has its companion planted in place.
+    pub(super) fn has_companion_at(&self, x: usize, y: usize) -> Result<bool, EngineError> {
+        let Some(companion) = self
+            .world
+            .get_tile(x, y)?
+            .entity()
+            .and_then(|entity| entity.companion())
+        else {
+            return Ok(false);
+        };
+        let (cx, cy) = companion.position();
+        // A companion left outside the world by a resize can never be planted.
+        Ok(self
+            .world
+            .get_tile(cx, cy)
+            .ok()
+            .and_then(|tile| tile.entity())
+            .is_some_and(|entity| entity.entity_type() == companion.entity_type()))
+    }
+
+    /// Rolls a new companion for every entity whose companion is outside
+    /// the world, as happens when it shrinks.
+    pub(super) fn reroll_lost_companions(&mut self) -> Result<(), EngineError> {
+        let (width, height) = (self.world.width(), self.world.height());
+        for x in 0..width {
+            for y in 0..height {
+                let Some(entity) = self.world.get_tile(x, y)?.entity() else {
+                    continue;
+                };
+                let lost = entity.companion().is_some_and(|companion| {
+                    let (cx, cy) = companion.position();
+                    cx >= width || cy >= height
+                });
+                if lost {
+                    let entity_type = entity.entity_type().clone();
+                    let companion = self.roll_companion(&entity_type, x, y);
+                    if let Some(entity) = self.world.mut_tile(x, y)?.mut_entity() {
+                        entity.set_companion(companion);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        consts,
+        game::{inventory::Item, testing::TestGame, Game},
+        world::{
+            entities::{Companion, Entity, EntityType},
+            tiles::GroundType,
+        },
+    };
+
+    fn plant_companion(game: &mut Game) {
+        let companion = game.get_companion().unwrap().unwrap().clone();
+        let (x, y) = companion.position();
+        let entity = Entity::new(companion.entity_type().clone(), game.now());
+        let tile = game.world.mut_tile(x, y).unwrap();
+        if companion.entity_type() == &EntityType::Carrot {
+            tile.set_ground_type(GroundType::Soil);
+        }
+        tile.set_entity(Some(entity)).unwrap();
+    }
+
+    #[test]
+    fn planted_entity_asks_for_nearby_companion_of_another_type() {
+        let mut game = TestGame::new(9, 9).build();
+        game.plant(EntityType::Bush).unwrap();
+
+        let companion = game.get_companion().unwrap().unwrap();
+        let (x, y) = companion.position();
+        assert_ne!(companion.entity_type(), &EntityType::Bush);
+        assert_ne!((x, y), (0, 0));
+        let distance = x.min(9 - x) + y.min(9 - y);
+        assert!(distance <= consts::COMPANION_MAX_DISTANCE);
+    }
+
+    #[test]
+    fn only_polyculture_entities_have_companions() {
+        let mut game = TestGame::new(9, 9).item(Item::Carrot, 1).build();
+        game.till().unwrap();
+        game.plant(EntityType::Pumpkin).unwrap();
+
+        assert!(game.get_companion().unwrap().is_none());
+    }
+
+    #[test]
+    fn empty_tile_has_no_companion() {
+        let mut game = TestGame::new(9, 9).build();
+        assert!(game.get_companion().unwrap().is_none());
+    }
+
+    #[test]
+    fn satisfied_companion_multiplies_yield() {
+        let mut game = TestGame::new(9, 9).build();
+        game.plant(EntityType::Tree).unwrap();
+        plant_companion(&mut game);
+
+        game.advance_time(EntityType::Tree.growth_time());
+        game.harvest().unwrap();
+        assert_eq!(
+            game.inventory().count(Item::Wood),
+            consts::TREE_WOOD_YIELD * consts::COMPANION_YIELD_MULTIPLIER
+        );
+    }
+
+    #[test]
+    fn unsatisfied_companion_yields_normally() {
+        let mut game = TestGame::new(9, 9).build();
+        game.plant(EntityType::Tree).unwrap();
+
+        game.advance_time(EntityType::Tree.growth_time());
+        game.harvest().unwrap();
+        assert_eq!(game.inventory().count(Item::Wood), consts::TREE_WOOD_YIELD);
+    }
+
+    #[test]
+    fn can_harvest_after_shrinking_past_companion() {
+        let mut game = TestGame::new(9, 9).build();
+        game.plant(EntityType::Bush).unwrap();
+        let tile = game.world.mut_tile(0, 0).unwrap();
+        tile.mut_entity()
+            .unwrap()
+            .set_companion(Some(Companion::new(EntityType::Grass, (0, 8))));
+
+        game.resize_world(4, 4).unwrap();
+        let (x, y) = game.get_companion().unwrap().unwrap().position();
+        assert!(x < 4 && y < 4);
+
+        game.advance_time(EntityType::Bush.growth_time());
+        game.harvest().unwrap();
+        assert_eq!(game.inventory().count(Item::Wood), consts::BUSH_WOOD_YIELD);
+    }
+
+    #[test]
+    fn companion_outside_world_is_not_satisfied() {
+        let mut game = TestGame::new(9, 9).build();
+        game.plant(EntityType::Bush).unwrap();
+        let tile = game.world.mut_tile(0, 0).unwrap();
+        tile.mut_entity()
+            .unwrap()
+            .set_companion(Some(Companion::new(EntityType::Grass, (0, 20))));
+
+        assert!(!game.has_companion_at(0, 0).unwrap());
+    }
+}
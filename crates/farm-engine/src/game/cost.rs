@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::consts;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Operation {
+    Move,
+    Till,
+    Plant,
+    Harvest,
+    CanHarvest,
+    GetEntityType,
+    GetGroundType,
+    GetPosX,
+    GetPosY,
+    GetWorldSize,
+    NumItems,
+    UseItem,
+    GetWater,
+    Measure,
+    Swap,
+    GetCompanion,
+    CanMove,
+    ChangeHat,
+    Unlock,
+    NumUnlocked,
+    SpawnDrone,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OperationCosts {
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_util::sorted_map")
+    )]
+    ticks: HashMap<Operation, u64>,
+}
+
+impl OperationCosts {
+    pub fn ticks(&self, operation: Operation) -> u64 {
+        self.ticks.get(&operation).copied().unwrap_or_default()
+    }
+
+    pub fn set_ticks(&mut self, operation: Operation, ticks: u64) -> &mut Self {
+        self.ticks.insert(operation, ticks);
+        self
+    }
+}
+
+impl Default for OperationCosts {
+    fn default() -> Self {
+        let ticks = HashMap::from([
+            (Operation::Move, consts::MOVE_TICKS),
+            (Operation::Till, consts::TILL_TICKS),
+            (Operation::Plant, consts::PLANT_TICKS),
+            (Operation::Harvest, consts::HARVEST_TICKS),
+            (Operation::CanHarvest, consts::QUERY_TICKS),
+            (Operation::GetEntityType, consts::QUERY_TICKS),
+            (Operation::GetGroundType, consts::QUERY_TICKS),
+            (Operation::GetPosX, consts::QUERY_TICKS),
+            (Operation::GetPosY, consts::QUERY_TICKS),
+            (Operation::GetWorldSize, consts::QUERY_TICKS),
+            (Operation::NumItems, consts::QUERY_TICKS),
+            (Operation::UseItem, consts::USE_ITEM_TICKS),
+            (Operation::GetWater, consts::QUERY_TICKS),
+            (Operation::Measure, consts::QUERY_TICKS),
+            (Operation::Swap, consts::SWAP_TICKS),
+            (Operation::GetCompanion, consts::QUERY_TICKS),
+            (Operation::CanMove, consts::QUERY_TICKS),
+            (Operation::ChangeHat, consts::CHANGE_HAT_TICKS),
+            (Operation::Unlock, consts::UNLOCK_TICKS),
+            (Operation::NumUnlocked, consts::QUERY_TICKS),
+            (Operation::SpawnDrone, consts::SPAWN_DRONE_TICKS),
+        ]);
+        Self { ticks }
+    }
+}
+
+pub fn ticks_to_duration(ticks: u64) -> Duration {
+    Duration::from_micros(ticks * 1_000_000 / consts::TICKS_PER_SECOND)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_costs_match_game_table() {
+        let costs = OperationCosts::default();
+        assert_eq!(costs.ticks(Operation::Move), consts::MOVE_TICKS);
+        assert_eq!(costs.ticks(Operation::Harvest), consts::HARVEST_TICKS);
+        assert_eq!(costs.ticks(Operation::CanHarvest), consts::QUERY_TICKS);
+    }
+
+    #[test]
+    fn can_override_operation_cost() {
+        let mut costs = OperationCosts::default();
+        costs.set_ticks(Operation::Till, 0);
+        assert_eq!(costs.ticks(Operation::Till), 0);
+    }
+
+    #[test]
+    fn converts_ticks_to_engine_time() {
+        assert_eq!(
+            ticks_to_duration(consts::TICKS_PER_SECOND),
+            Duration::from_secs(1)
+        );
+        assert_eq!(ticks_to_duration(200), Duration::from_millis(200));
+    }
+}
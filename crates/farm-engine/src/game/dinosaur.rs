@@ -0,0 +1,355 @@
+use rand::Rng;
+
+use crate::{
+    consts,
+    error::EngineError,
+    game::{inventory::Item, Direction, Game, Hat},
+    world::entities::{Entity, EntityType},
+};
+
+impl Game {
+    /// Where the apple is. Dinosaurs share a single apple, so there is at
+    /// most one unless the world was loaded with more.
+    pub(super) fn apple_position(&self) -> Option<(usize, usize)> {
+        (0..self.world.width())
+            .flat_map(|x| (0..self.world.height()).map(move |y| (x, y)))
+            .find(|&(x, y)| {
+                self.world
+                    .get_tile(x, y)
+                    .ok()
+                    .and_then(|tile| tile.entity())
+                    .is_some_and(|entity| entity.entity_type() == &EntityType::Apple)
+            })
+    }
+
+    /// Places an apple on a random empty tile not covered by a drone or a
+    /// tail. No apple spawns once no such tile is left.
+    pub(super) fn spawn_apple(&mut self) -> Result<(), EngineError> {
+        let covered = |position: &(usize, usize)| {
+            self.drones
+                .iter()
+                .any(|drone| (drone.x(), drone.y()) == *position || drone.tail().contains(position))
+        };
+        let empty = |&(x, y): &(usize, usize)| {
+            self.world
+                .get_tile(x, y)
+                .is_ok_and(|tile| tile.entity().is_none())
+        };
+        let free: Vec<_> = (0..self.world.width())
+            .flat_map(|x| (0..self.world.height()).map(move |y| (x, y)))
+            .filter(|position| !covered(position) && empty(position))
+            .collect();
+        if free.is_empty() {
+            return Ok(());
+        }
+
+        let (x, y) = free[self.rng.random_range(0..free.len())];
+        let apple = Entity::new(EntityType::Apple, self.clock.now());
+        self.world.mut_tile(x, y)?.set_entity(Some(apple))?;
+        self.update_tree_growth_around(x, y)
+    }
+
+    /// Moves the dinosaur to `(x, y)`, eating the apple there. The tip of
+    /// the tail moves out of the way, every other tail tile blocks the move.
+    pub(super) fn move_dinosaur(
+        &mut self,
+        direction: Direction,
+        x: usize,
+        y: usize,
+    ) -> Result<(), EngineError> {
+        if self.tail_blocks(x, y) {
+            return Err(EngineError::BlockedByTail(direction));
+        }
+
+        let eats = self
+            .world
+            .get_tile(x, y)?
+            .entity()
+            .is_some_and(|entity| entity.entity_type() == &EntityType::Apple);
+        self.mut_drone().drag_to(x, y, eats);
+        if eats {
+            self.world.mut_tile(x, y)?.set_entity(None)?;
+            if self.apple_position().is_none() {
+                self.spawn_apple()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the active drone's tail is in the way of moving to `(x, y)`.
+    /// The tip of the tail moves out of the way, so it never blocks.
+    pub(super) fn tail_blocks(&self, x: usize, y: usize) -> bool {
+        let tail = self.drone().tail();
+        tail.contains(&(x, y)) && tail.back() != Some(&(x, y))
+    }
+
+    /// Gives the active drone the dinosaur hat, spawning the apple if no
+    /// other dinosaur already has one to chase.
+    pub(super) fn put_on_dinosaur_hat(&mut self) -> Result<(), EngineError> {
+        self.mut_drone().set_hat(Hat::Dinosaur);
+        if self.apple_position().is_none() {
+            self.spawn_apple()?;
+        }
+        Ok(())
+    }
+
+    /// Trades the tail in for bones, growing quadratically with its length.
+    /// The apple goes away with the last dinosaur.
+    pub(super) fn take_off_dinosaur_hat(&mut self) -> Result<(), EngineError> {
+        let length = self.drone().tail().len() as u64;
+        self.inventory
+            .add(Item::Bone, length * length * consts::BONES_PER_TAIL_SQUARE);
+        self.mut_drone().set_hat(Hat::Straw).clear_tail();
+        if self.drones.iter().all(|drone| drone.hat() != Hat::Dinosaur) {
+            while let Some((x, y)) = self.apple_position() {
+                self.world.mut_tile(x, y)?.set_entity(None)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        consts,
+        error::EngineError,
+        game::{
+            inventory::Item, testing::TestGame, Direction, Game, GameOptions, Hat, Measurement,
+        },
+        world::entities::EntityType,
+    };
+
+    fn create_dinosaur_game(size: usize) -> Game {
+        let mut game = TestGame::new(size, size).build();
+        game.change_hat(Hat::Dinosaur).unwrap();
+        game
+    }
+
+    fn apple(game: &mut Game) -> (usize, usize) {
+        let Some(Measurement::Position(x, y)) = game.measure().unwrap() else {
+            panic!("no apple to eat");
+        };
+        (x, y)
+    }
+
+    /// Walks the drone straight to `target` without wrapping.
+    fn walk_to(game: &mut Game, (x, y): (usize, usize)) {
+        while game.drone_position().0 < x {
+            game.move_drone(Direction::East).unwrap();
+        }
+        while game.drone_position().0 > x {
+            game.move_drone(Direction::West).unwrap();
+        }
+        while game.drone_position().1 < y {
+            game.move_drone(Direction::South).unwrap();
+        }
+        while game.drone_position().1 > y {
+            game.move_drone(Direction::North).unwrap();
+        }
+    }
+
+    /// Hamiltonian cycle through a square world with an even side.
+    fn hamiltonian_cycle(size: usize) -> Vec<Direction> {
+        let mut directions = vec![Direction::South; size - 1];
+        for column in 0..size - 1 {
+            let vertical = if column % 2 == 0 {
+                Direction::North
+            } else {
+                Direction::South
+            };
+            directions.push(Direction::East);
+            directions.extend(vec![vertical; size - 2]);
+        }
+        directions.push(Direction::North);
+        directions.extend(vec![Direction::West; size - 1]);
+        directions
+    }
+
+    #[test]
+    fn wearing_dinosaur_hat_spawns_an_apple() {
+        let mut game = create_dinosaur_game(5);
+
+        let (x, y) = apple(&mut game);
+        assert_ne!((x, y), game.drone_position());
+        assert_eq!(game.drone_hat(), Hat::Dinosaur);
+        assert!(game.drone_tail().is_empty());
+    }
+
+    #[test]
+    fn eating_apple_grows_tail_and_spawns_next_apple() {
+        let mut game = create_dinosaur_game(5);
+
+        let first = apple(&mut game);
+        walk_to(&mut game, first);
+        assert_eq!(game.drone_tail().len(), 1);
+        assert_ne!(apple(&mut game), first);
+    }
+
+    #[test]
+    fn cannot_move_into_tail() {
+        let mut game = create_dinosaur_game(5);
+        for _ in 0..2 {
+            let next = apple(&mut game);
+            walk_to(&mut game, next);
+        }
+        let (x, y) = game.drone_position();
+        let direction = Direction::ALL
+            .into_iter()
+            .find(|&direction| game.world().neighbor(x, y, direction) == game.drone_tail()[0])
+            .unwrap();
+
+        assert!(matches!(
+            game.move_drone(direction).unwrap_err(),
+            EngineError::BlockedByTail(blocked) if blocked == direction
+        ));
+        assert_eq!(game.drone_position(), (x, y));
+    }
+
+    #[test]
+    fn can_move_reports_tail_blocking() {
+        let mut game = create_dinosaur_game(5);
+        for _ in 0..2 {
+            let next = apple(&mut game);
+            walk_to(&mut game, next);
+        }
+        let (x, y) = game.drone_position();
+        let direction = Direction::ALL
+            .into_iter()
+            .find(|&direction| game.world().neighbor(x, y, direction) == game.drone_tail()[0])
+            .unwrap();
+
+        assert!(!game.can_move(direction));
+    }
+
+    #[test]
+    fn apples_only_spawn_on_empty_tiles() {
+        let mut game = TestGame::new(2, 1).build();
+        game.move_drone(Direction::East).unwrap();
+        game.plant(EntityType::Tree).unwrap();
+        game.move_drone(Direction::East).unwrap();
+        game.change_hat(Hat::Dinosaur).unwrap();
+
+        assert_eq!(game.measure().unwrap(), None);
+        assert_eq!(
+            game.world()
+                .get_tile(1, 0)
+                .unwrap()
+                .entity()
+                .unwrap()
+                .entity_type(),
+            &EntityType::Tree
+        );
+    }
+
+    #[test]
+    fn dinosaurs_share_one_apple() {
+        let apples = |game: &Game| {
+            game.world()
+                .tiles()
+                .filter(|tile| {
+                    tile.entity()
+                        .is_some_and(|entity| entity.entity_type() == &EntityType::Apple)
+                })
+                .count()
+        };
+        let mut game = create_dinosaur_game(4);
+        let first = game.active_drone();
+        let second = game.spawn_drone().unwrap();
+        game.select_drone(second).unwrap();
+        game.change_hat(Hat::Dinosaur).unwrap();
+        assert_eq!(apples(&game), 1);
+
+        game.change_hat(Hat::Straw).unwrap();
+        assert_eq!(apples(&game), 1);
+        game.select_drone(first).unwrap();
+        game.change_hat(Hat::Straw).unwrap();
+        assert_eq!(apples(&game), 0);
+    }
+
+    #[test]
+    fn dinosaur_eats_every_apple_it_reaches() {
+        let mut game = Game::from_grid_str(GameOptions::default(), "AAD").unwrap();
+        game.change_hat(Hat::Dinosaur).unwrap();
+
+        game.move_drone(Direction::West).unwrap();
+        game.move_drone(Direction::West).unwrap();
+        assert_eq!(game.drone().tail().len(), 2);
+        assert_eq!(game.measure().unwrap(), None);
+    }
+
+    #[test]
+    fn planting_over_apple_spawns_a_new_one() {
+        let mut game = create_dinosaur_game(3);
+        let first = apple(&mut game);
+        let dinosaur = game.active_drone();
+        let gardener = game.spawn_drone().unwrap();
+        game.select_drone(gardener).unwrap();
+        walk_to(&mut game, first);
+        game.plant(EntityType::Bush).unwrap();
+
+        game.select_drone(dinosaur).unwrap();
+        let next = apple(&mut game);
+        assert_ne!(next, first);
+        assert_eq!(
+            game.world()
+                .get_tile(first.0, first.1)
+                .unwrap()
+                .entity()
+                .unwrap()
+                .entity_type(),
+            &EntityType::Bush
+        );
+    }
+
+    #[test]
+    fn can_move_into_tip_of_tail() {
+        let mut game = create_dinosaur_game(5);
+        let next = apple(&mut game);
+        walk_to(&mut game, next);
+        let (x, y) = game.drone_position();
+        let tip = game.drone_tail()[0];
+        let direction = Direction::ALL
+            .into_iter()
+            .find(|&direction| game.world().neighbor(x, y, direction) == tip)
+            .unwrap();
+
+        game.move_drone(direction).unwrap();
+        assert_eq!(game.drone_position(), tip);
+        assert_eq!(game.drone_tail()[0], (x, y));
+    }
+
+    #[test]
+    fn following_a_hamiltonian_cycle_never_hits_the_tail() {
+        let mut game = create_dinosaur_game(4);
+        let cycle = hamiltonian_cycle(4);
+        assert_eq!(cycle.len(), 16);
+
+        // Every lap passes the apple, so the tail grows at least once per lap
+        // until it fills the world.
+        for _ in 0..16 {
+            for &direction in &cycle {
+                game.move_drone(direction).unwrap();
+            }
+        }
+        assert_eq!(game.drone_tail().len(), 15);
+        assert_eq!(game.measure().unwrap(), None);
+    }
+
+    #[test]
+    fn taking_off_hat_trades_tail_for_bones() {
+        let mut game = create_dinosaur_game(5);
+        for _ in 0..3 {
+            let next = apple(&mut game);
+            walk_to(&mut game, next);
+        }
+
+        game.change_hat(Hat::Straw).unwrap();
+        assert_eq!(
+            game.inventory().count(Item::Bone),
+            9 * consts::BONES_PER_TAIL_SQUARE
+        );
+        assert!(game.drone_tail().is_empty());
+        assert!(game.world().tiles().all(|tile| tile.entity().is_none()));
+    }
+}
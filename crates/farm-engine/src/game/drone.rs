@@ -1,11 +1,25 @@
+use std::collections::VecDeque;
+
+use crate::game::Hat;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Drone {
     x: usize,
     y: usize,
+    hat: Hat,
+    // Tiles the dinosaur's tail covers, nearest to the drone first.
+    tail: VecDeque<(usize, usize)>,
 }
 
 impl Drone {
     pub fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
+        Self {
+            x,
+            y,
+            hat: Hat::default(),
+            tail: VecDeque::new(),
+        }
     }
     pub fn x(&self) -> usize {
         self.x
@@ -18,4 +32,53 @@ impl Drone {
         self.y = y;
         self
     }
+    pub fn hat(&self) -> Hat {
+        self.hat
+    }
+    pub fn set_hat(&mut self, hat: Hat) -> &mut Self {
+        self.hat = hat;
+        self
+    }
+    pub fn tail(&self) -> &VecDeque<(usize, usize)> {
+        &self.tail
+    }
+    /// Moves to `(x, y)` with the tail following behind. The tail gets one
+    /// tile longer when `grow` is set.
+    pub fn drag_to(&mut self, x: usize, y: usize, grow: bool) -> &mut Self {
+        self.tail.push_front((self.x, self.y));
+        if !grow {
+            self.tail.pop_back();
+        }
+        self.set_position(x, y)
+    }
+    /// Cuts the tail off at the first tile for which `keep` fails.
+    pub fn cut_tail(&mut self, keep: impl Fn(usize, usize) -> bool) -> &mut Self {
+        let length = self
+            .tail
+            .iter()
+            .position(|&(x, y)| !keep(x, y))
+            .unwrap_or(self.tail.len());
+        self.tail.truncate(length);
+        self
+    }
+    pub fn clear_tail(&mut self) -> &mut Self {
+        self.tail.clear();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_follows_the_drone() {
+        let mut drone = Drone::new(0, 0);
+        drone.drag_to(1, 0, true).drag_to(2, 0, false);
+        assert_eq!(drone.tail(), &VecDeque::from([(1, 0)]));
+
+        drone.drag_to(3, 0, true);
+        assert_eq!(drone.tail(), &VecDeque::from([(2, 0), (1, 0)]));
+        assert_eq!((drone.x(), drone.y()), (3, 0));
+    }
 }
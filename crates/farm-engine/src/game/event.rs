@@ -0,0 +1,297 @@
+use std::time::Duration;
+
+use crate::{
+    game::{inventory::Item, DroneId, Game},
+    world::{entities::EntityType, tiles::GroundType},
+};
+
+/// Something that changed in a `Game`, sent to every subscribed observer.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Event {
+    DroneMoved {
+        drone: DroneId,
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+    Tilled {
+        drone: DroneId,
+        position: (usize, usize),
+        ground_type: GroundType,
+    },
+    Planted {
+        drone: DroneId,
+        position: (usize, usize),
+        entity_type: EntityType,
+    },
+    /// `item` is `None` when the harvested entity yields nothing. `cleared`
+    /// lists every tile emptied, which is more than `position` for cactus
+    /// chains, giant pumpkins and mazes.
+    Harvested {
+        drone: DroneId,
+        position: (usize, usize),
+        entity_type: EntityType,
+        item: Option<Item>,
+        amount: u64,
+        cleared: Vec<(usize, usize)>,
+    },
+    /// Sent when the game clock passes the moment an entity finishes growing.
+    EntityGrown {
+        position: (usize, usize),
+        entity_type: EntityType,
+    },
+    WorldCleared,
+}
+
+/// Receives the events of a game it is subscribed to. Implemented for
+/// closures taking an `&Event`.
+pub trait Observer: Send {
+    fn notify(&mut self, event: &Event);
+}
+
+impl<F: FnMut(&Event) + Send> Observer for F {
+    fn notify(&mut self, event: &Event) {
+        self(event)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct ObserverId(usize);
+
+impl Game {
+    pub fn subscribe(&mut self, observer: impl Observer + 'static) -> ObserverId {
+        let id = ObserverId(self.next_observer_id);
+        self.next_observer_id += 1;
+        self.observers.push((id, Box::new(observer)));
+        id
+    }
+
+    /// Removes the observer, returning whether it was subscribed.
+    pub fn unsubscribe(&mut self, id: ObserverId) -> bool {
+        let count = self.observers.len();
+        self.observers.retain(|(observer, _)| *observer != id);
+        self.observers.len() < count
+    }
+
+    /// Sends the event built by `event` to every observer. The event is
+    /// only built when someone is subscribed.
+    pub(super) fn emit(&mut self, event: impl FnOnce(&Game) -> Event) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let event = event(self);
+        for (_, observer) in &mut self.observers {
+            observer.notify(&event);
+        }
+    }
+
+    /// Emits `EntityGrown` for each entity that was still growing at
+    /// `before` but is grown now. The world is only scanned once the
+    /// earliest growing entity could be done.
+    pub(super) fn emit_grown_since(&mut self, before: Duration) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let now = self.now();
+        if self.world.growth_due().is_some_and(|due| now < due) {
+            return;
+        }
+        let mut due = Duration::MAX;
+        let (width, height) = (self.world.width(), self.world.height());
+        for x in 0..width {
+            for y in 0..height {
+                let Ok(tile) = self.world.get_tile(x, y) else {
+                    continue;
+                };
+                if !tile.is_grown(now) {
+                    if let Some(grown_at) = tile.earliest_grown_at(now) {
+                        due = due.min(grown_at);
+                    }
+                } else if let Some(entity) = tile.entity() {
+                    if !tile.is_grown(before) {
+                        let entity_type = entity.entity_type().clone();
+                        self.emit(|_| Event::EntityGrown {
+                            position: (x, y),
+                            entity_type,
+                        });
+                    }
+                }
+            }
+        }
+        self.world.set_growth_due(due);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{
+        game::{testing::TestGame, Direction},
+        world::World,
+    };
+
+    fn record_events(game: &mut Game) -> Arc<Mutex<Vec<Event>>> {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        game.subscribe(move |event: &Event| sink.lock().unwrap().push(event.clone()));
+        events
+    }
+
+    #[test]
+    fn observers_receive_action_events() {
+        let mut game = TestGame::new(4, 4).build();
+        let events = record_events(&mut game);
+        let drone = game.active_drone();
+
+        game.move_drone(Direction::East).unwrap();
+        game.till().unwrap();
+        game.till().unwrap();
+        game.plant(EntityType::Bush).unwrap();
+        game.clear().unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                Event::DroneMoved {
+                    drone,
+                    from: (0, 0),
+                    to: (1, 0),
+                },
+                Event::Tilled {
+                    drone,
+                    position: (1, 0),
+                    ground_type: GroundType::Soil,
+                },
+                Event::Tilled {
+                    drone,
+                    position: (1, 0),
+                    ground_type: GroundType::Grassland,
+                },
+                Event::Planted {
+                    drone,
+                    position: (1, 0),
+                    entity_type: EntityType::Bush,
+                },
+                Event::WorldCleared,
+            ]
+        );
+    }
+
+    #[test]
+    fn observers_see_growth_and_harvest() {
+        let mut game = TestGame::new(4, 4).build();
+        let events = record_events(&mut game);
+        game.plant(EntityType::Bush).unwrap();
+        events.lock().unwrap().clear();
+
+        game.advance_time(EntityType::Bush.growth_time());
+        game.harvest().unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                Event::EntityGrown {
+                    position: (0, 0),
+                    entity_type: EntityType::Bush,
+                },
+                Event::Harvested {
+                    drone: game.active_drone(),
+                    position: (0, 0),
+                    entity_type: EntityType::Bush,
+                    item: Some(Item::Wood),
+                    amount: crate::consts::BUSH_WOOD_YIELD,
+                    cleared: vec![(0, 0)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn world_is_only_scanned_once_growth_is_due() {
+        let mut game = TestGame::new(4, 4).build();
+        let events = record_events(&mut game);
+        game.plant(EntityType::Bush).unwrap();
+        let planted = game.now();
+        assert!(game.world().growth_due().is_none());
+        game.get_pos_x();
+        let due = game.world().growth_due().unwrap();
+        assert!(due > planted && due <= planted + EntityType::Bush.growth_time());
+
+        game.mut_inventory().add(Item::Water, 1);
+        game.use_item(Item::Water).unwrap();
+        game.get_pos_x();
+        assert!(game.world().growth_due().unwrap() < due);
+        events.lock().unwrap().clear();
+
+        let watered_due = game.world().growth_due().unwrap();
+        game.advance_time(watered_due.saturating_sub(game.now()));
+        while events.lock().unwrap().is_empty() {
+            game.advance_time(std::time::Duration::from_millis(10));
+        }
+        assert!(game.now() < planted + EntityType::Bush.growth_time());
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![Event::EntityGrown {
+                position: (0, 0),
+                entity_type: EntityType::Bush,
+            }]
+        );
+    }
+
+    #[test]
+    fn fertilizing_to_full_growth_emits_entity_grown() {
+        let mut game = TestGame::new(4, 4).build();
+        let events = record_events(&mut game);
+        game.plant(EntityType::Grass).unwrap();
+        game.mut_inventory().add(Item::Fertilizer, 1);
+        events.lock().unwrap().clear();
+
+        game.use_item(Item::Fertilizer).unwrap();
+        assert!(game.can_harvest().unwrap());
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![Event::EntityGrown {
+                position: (0, 0),
+                entity_type: EntityType::Grass,
+            }]
+        );
+    }
+
+    #[test]
+    fn harvested_lists_every_cleared_tile() {
+        let mut game = TestGame::new(4, 4).build();
+        let events = record_events(&mut game);
+        game.world = World::from_ascii("SS\nXX\n@0,0 grown size=1\n@1,0 grown size=1").unwrap();
+
+        game.harvest().unwrap();
+        let Some(Event::Harvested { cleared, .. }) = events.lock().unwrap().last().cloned() else {
+            panic!("no harvest event");
+        };
+        let mut cleared = cleared;
+        cleared.sort();
+        assert_eq!(cleared, vec![(0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn failed_actions_emit_nothing() {
+        let mut game = TestGame::new(4, 4).build();
+        let events = record_events(&mut game);
+        game.harvest().unwrap_err();
+        game.plant(EntityType::Carrot).unwrap_err();
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn can_unsubscribe() {
+        let mut game = TestGame::new(4, 4).build();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let id = game.subscribe(move |event: &Event| sink.lock().unwrap().push(event.clone()));
+
+        assert!(game.unsubscribe(id));
+        assert!(!game.unsubscribe(id));
+        game.till().unwrap();
+        assert!(events.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use crate::error::EngineError;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Item {
+    Hay,
+    Wood,
+    Carrot,
+    Pumpkin,
+    Water,
+    Fertilizer,
+    Power,
+    Cactus,
+    WeirdSubstance,
+    Gold,
+    Bone,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Inventory {
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_util::sorted_map")
+    )]
+    items: HashMap<Item, u64>,
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn count(&self, item: Item) -> u64 {
+        self.items.get(&item).copied().unwrap_or_default()
+    }
+
+    pub fn add(&mut self, item: Item, amount: u64) -> &mut Self {
+        *self.items.entry(item).or_default() += amount;
+        self
+    }
+
+    pub fn check(&self, cost: &[(Item, u64)]) -> Result<(), EngineError> {
+        for &(item, needed) in cost {
+            let have = self.count(item);
+            if have < needed {
+                return Err(EngineError::InsufficientResources { item, needed, have });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, cost: &[(Item, u64)]) -> Result<(), EngineError> {
+        self.check(cost)?;
+        for &(item, amount) in cost {
+            *self.items.entry(item).or_default() -= amount;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_inventory_is_empty() {
+        let inventory = Inventory::new();
+        assert_eq!(inventory.count(Item::Hay), 0);
+        assert_eq!(inventory.count(Item::Pumpkin), 0);
+    }
+
+    #[test]
+    fn can_add_items() {
+        let mut inventory = Inventory::new();
+        inventory.add(Item::Wood, 3).add(Item::Wood, 2);
+        assert_eq!(inventory.count(Item::Wood), 5);
+        assert_eq!(inventory.count(Item::Hay), 0);
+    }
+
+    #[test]
+    fn can_remove_affordable_cost() {
+        let mut inventory = Inventory::new();
+        inventory.add(Item::Hay, 2).add(Item::Wood, 2);
+
+        inventory
+            .remove(&[(Item::Hay, 1), (Item::Wood, 2)])
+            .unwrap();
+        assert_eq!(inventory.count(Item::Hay), 1);
+        assert_eq!(inventory.count(Item::Wood), 0);
+    }
+
+    #[test]
+    fn removing_unaffordable_cost_leaves_inventory_untouched() {
+        let mut inventory = Inventory::new();
+        inventory.add(Item::Hay, 2).add(Item::Wood, 1);
+
+        let result = inventory.remove(&[(Item::Hay, 1), (Item::Wood, 3)]);
+        assert!(matches!(
+            result.unwrap_err(),
+            EngineError::InsufficientResources {
+                item: Item::Wood,
+                needed: 3,
+                have: 1
+            }
+        ));
+        assert_eq!(inventory.count(Item::Hay), 2);
+        assert_eq!(inventory.count(Item::Wood), 1);
+    }
+}
@@ -0,0 +1,361 @@
+use std::time::Duration;
+
+use crate::{
+    error::EngineError,
+    game::{action::Action, inventory::Item, DroneId, Game},
+};
+
+/// One recorded action and how it turned out.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoggedAction {
+    pub drone: DroneId,
+    /// Elapsed ticks when the action started.
+    pub tick: u64,
+    /// Game time when the action started. Differs from `tick` once time
+    /// has been advanced by hand.
+    pub now: Duration,
+    /// Ticks the action cost.
+    pub ticks: u64,
+    /// Power in the inventory when the action started.
+    pub power: u64,
+    /// Operations sped up by power so far, which decides when the next unit
+    /// of power is used up.
+    pub powered_operations: u64,
+    /// Whether the action ran alongside others in the scheduler.
+    pub parallel: bool,
+    pub action: Action,
+    /// The error message if the action failed.
+    pub result: Result<(), String>,
+}
+
+/// Actions taken in a game, in the order they ran. Enabled with
+/// `GameOptions::record_actions`.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionLog {
+    actions: Vec<LoggedAction>,
+}
+
+impl ActionLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn actions(&self) -> &[LoggedAction] {
+        &self.actions
+    }
+
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    pub(super) fn truncate(&mut self, len: usize) {
+        self.actions.truncate(len);
+    }
+}
+
+/// Re-executes an `ActionLog` and checks every action turns out the same
+/// as when it was recorded.
+pub struct Replay {
+    log: ActionLog,
+}
+
+impl Replay {
+    pub fn new(log: ActionLog) -> Self {
+        Self { log }
+    }
+
+    /// Replays the log against `game`, which should be set up like the
+    /// recorded game was before its first action: same options, seed, and
+    /// world. Time skipped with `advance_time`, and the time and power
+    /// spent on queries, are caught up on before each action, after which
+    /// the game must be in the recorded state.
+    pub fn run(&self, game: &mut Game) -> Result<(), EngineError> {
+        for (index, expected) in self.log.actions.iter().enumerate() {
+            game.advance_to_tick(expected.tick);
+            if let Some(skipped) = expected.now.checked_sub(game.now()) {
+                game.advance_time(skipped);
+            }
+            while game.powered_operations < expected.powered_operations && game.use_power() {}
+            let state = (
+                game.now(),
+                game.inventory.count(Item::Power),
+                game.powered_operations,
+            );
+            if state != (expected.now, expected.power, expected.powered_operations) {
+                return Err(EngineError::ReplayDiverged {
+                    index,
+                    reason: format!(
+                        "Expected time, power and powered operations {:?}, got {state:?}.",
+                        (expected.now, expected.power, expected.powered_operations)
+                    ),
+                });
+            }
+            let actual = game.replay_action(expected);
+            if actual.as_ref() != Some(expected) {
+                return Err(EngineError::ReplayDiverged {
+                    index,
+                    reason: format!("Expected {expected:?}, got {actual:?}."),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Game {
+    pub fn action_log(&self) -> Option<&ActionLog> {
+        self.action_log.as_ref()
+    }
+
+    /// Returns the log recorded so far and starts a new one.
+    pub fn take_action_log(&mut self) -> Option<ActionLog> {
+        self.action_log.as_mut().map(std::mem::take)
+    }
+
+    /// Runs `perform` and records it as `action` if actions are being
+    /// recorded. Actions started by a recorded action are not recorded.
+    pub(super) fn logged<T>(
+        &mut self,
+        action: Action,
+        perform: impl FnOnce(&mut Game) -> Result<T, EngineError>,
+    ) -> Result<T, EngineError> {
+        let Some(mut log) = self.action_log.take() else {
+            return perform(self);
+        };
+        let (drone, tick, now) = (self.active_drone, self.elapsed_ticks, self.now());
+        let (power, powered_operations) =
+            (self.inventory.count(Item::Power), self.powered_operations);
+        let spent = self.spent_ticks();
+        let result = perform(self);
+        log.actions.push(LoggedAction {
+            drone,
+            tick,
+            now,
+            ticks: self.spent_ticks() - spent,
+            power,
+            powered_operations,
+            parallel: self.deferred_ticks.is_some(),
+            action,
+            result: result.as_ref().map(|_| ()).map_err(ToString::to_string),
+        });
+        self.action_log = Some(log);
+        result
+    }
+
+    fn spent_ticks(&self) -> u64 {
+        self.elapsed_ticks + self.deferred_ticks.unwrap_or_default()
+    }
+
+    /// Performs `expected` the way it was recorded and returns how it went,
+    /// or `None` if its drone does not exist.
+    fn replay_action(&mut self, expected: &LoggedAction) -> Option<LoggedAction> {
+        let saved = self.action_log.replace(ActionLog::new());
+        // Scheduled actions ran with their drone selected only while they
+        // ran, other actions with it selected by the caller.
+        let found = if expected.parallel {
+            self.run_deferred(|game| {
+                game.with_drone(expected.drone, |game| {
+                    let _ = expected.action.apply(game);
+                })
+            })
+            .0
+        } else {
+            self.select_drone_unlogged(expected.drone).map(|()| {
+                let _ = expected.action.apply(self);
+            })
+        };
+        let actual = self
+            .action_log
+            .take()
+            .and_then(|mut log| log.actions.pop())
+            .filter(|_| found.is_ok());
+        self.action_log = saved;
+        if let (Some(log), Some(actual)) = (self.action_log.as_mut(), actual.as_ref()) {
+            log.actions.push(actual.clone());
+        }
+        actual
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        cost::ticks_to_duration, scheduler::Scheduler, testing::TestGame, unlock::Unlock,
+        Direction, GameOptions,
+    };
+    use crate::{consts, world::entities::EntityType};
+
+    fn play(game: &mut Game) {
+        game.till().unwrap();
+        game.plant(EntityType::Carrot).unwrap_err();
+        game.move_drone(Direction::East).unwrap();
+        game.plant(EntityType::Bush).unwrap();
+        game.get_pos_x();
+        game.advance_time(EntityType::Bush.growth_time());
+        game.harvest().unwrap();
+        game.unlock(Unlock::Speed).unwrap_err();
+        game.clear().unwrap();
+    }
+
+    #[test]
+    fn records_actions_with_ticks_and_results() {
+        let mut game = TestGame::new(5, 5).seed(11).record_actions().build();
+        play(&mut game);
+
+        let log = game.action_log().unwrap();
+        let actions: Vec<_> = log.actions().iter().map(|a| a.action.clone()).collect();
+        assert_eq!(
+            actions,
+            vec![
+                Action::Till,
+                Action::Plant(EntityType::Carrot),
+                Action::Move(Direction::East),
+                Action::Plant(EntityType::Bush),
+                Action::Harvest,
+                Action::Unlock(Unlock::Speed),
+                Action::Clear,
+            ]
+        );
+        assert_eq!(log.actions()[0].tick, 0);
+        assert_eq!(log.actions()[1].tick, log.actions()[0].ticks);
+        assert!(log.actions()[1].result.is_err());
+        assert!(log.actions()[4].now > ticks_to_duration(log.actions()[4].tick));
+    }
+
+    #[test]
+    fn does_not_record_by_default() {
+        let mut game = Game::new(GameOptions::default());
+        game.till().unwrap();
+        assert!(game.action_log().is_none());
+        assert!(game.take_action_log().is_none());
+    }
+
+    #[test]
+    fn can_replay_log_on_fresh_game() {
+        let test_game = TestGame::new(5, 5).seed(11).record_actions();
+        let mut game = test_game.build();
+        play(&mut game);
+        let log = game.take_action_log().unwrap();
+
+        let mut replayed = test_game.build();
+        Replay::new(log.clone()).run(&mut replayed).unwrap();
+
+        assert_eq!(replayed.action_log(), Some(&log));
+        assert_eq!(
+            replayed.get_world_grid_string(),
+            game.get_world_grid_string()
+        );
+        assert!(game.action_log().unwrap().is_empty());
+    }
+
+    #[test]
+    fn can_replay_scheduled_actions() {
+        let test_game = TestGame::new(5, 5).seed(11).record_actions();
+        let mut game = test_game.build();
+        let second = game.spawn_drone().unwrap();
+        let mut scheduler = Scheduler::new(3);
+        scheduler
+            .push(DroneId(0), Action::Plant(EntityType::Bush))
+            .push(second, Action::Move(Direction::North))
+            .push(second, Action::Plant(EntityType::Tree));
+        scheduler.run(&mut game).unwrap();
+        let log = game.take_action_log().unwrap();
+        assert!(log.actions()[1..].iter().all(|action| action.parallel));
+
+        let mut replayed = test_game.build();
+        Replay::new(log).run(&mut replayed).unwrap();
+        assert_eq!(
+            replayed.get_world_grid_string(),
+            game.get_world_grid_string()
+        );
+    }
+
+    #[test]
+    fn replay_catches_up_on_power_used_by_queries() {
+        let test_game = TestGame::new(5, 5).seed(11).record_actions();
+        let mut game = test_game.build();
+        game.mut_inventory().add(Item::Power, 1);
+        for _ in 0..consts::POWER_OPERATIONS_PER_UNIT - 1 {
+            game.get_pos_x();
+        }
+        game.move_drone(Direction::East).unwrap();
+        game.move_drone(Direction::East).unwrap();
+        let log = game.take_action_log().unwrap();
+        assert_ne!(log.actions()[0].ticks, log.actions()[1].ticks);
+
+        let mut replayed = test_game.build();
+        replayed.mut_inventory().add(Item::Power, 1);
+        Replay::new(log).run(&mut replayed).unwrap();
+        assert_eq!(replayed.inventory().count(Item::Power), 0);
+    }
+
+    #[test]
+    fn records_drone_selection_resizes_and_added_items() {
+        let test_game = TestGame::new(5, 5).seed(11).record_actions();
+        let mut game = test_game.build();
+        let second = game.spawn_drone().unwrap();
+        game.select_drone(second).unwrap();
+        game.move_drone(Direction::East).unwrap();
+        game.resize_world(3, 3).unwrap();
+        game.add_item(Item::Wood, 5);
+        let log = game.take_action_log().unwrap();
+        assert_eq!(
+            log.actions()[1..]
+                .iter()
+                .map(|a| a.action.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                Action::SelectDrone(second),
+                Action::Move(Direction::East),
+                Action::Resize(3, 3),
+                Action::AddItem(Item::Wood, 5),
+            ]
+        );
+
+        let mut replayed = test_game.build();
+        Replay::new(log).run(&mut replayed).unwrap();
+        assert_eq!(replayed.active_drone(), second);
+        assert_eq!(replayed.get_world_size(), (3, 3));
+        assert_eq!(replayed.drone_positions(), game.drone_positions());
+        assert_eq!(replayed.inventory().count(Item::Wood), 5);
+    }
+
+    #[test]
+    fn replay_detects_different_power() {
+        let test_game = TestGame::new(5, 5).seed(11).record_actions();
+        let mut game = test_game.build();
+        game.mut_inventory().add(Item::Power, 1);
+        game.move_drone(Direction::East).unwrap();
+        game.move_drone(Direction::East).unwrap();
+        let log = game.take_action_log().unwrap();
+
+        let mut replayed = test_game.build();
+        replayed.mut_inventory().add(Item::Power, 50);
+        assert!(matches!(
+            Replay::new(log).run(&mut replayed),
+            Err(EngineError::ReplayDiverged { index: 0, .. })
+        ));
+        assert_eq!(replayed.inventory().count(Item::Power), 50);
+    }
+
+    #[test]
+    fn replay_detects_divergence() {
+        let test_game = TestGame::new(5, 5).seed(11).record_actions();
+        let mut game = test_game.build();
+        play(&mut game);
+        let log = game.take_action_log().unwrap();
+
+        let mut replayed = test_game.locked().build();
+        assert!(matches!(
+            Replay::new(log).run(&mut replayed),
+            Err(EngineError::ReplayDiverged { index: 0, .. })
+        ));
+    }
+}
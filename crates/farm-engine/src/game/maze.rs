@@ -0,0 +1,292 @@
+use rand::Rng;
+
+use crate::{
+    error::EngineError,
+    game::{inventory::Item, Direction, Game},
+    world::{
+        entities::{Entity, EntityType},
+        tiles::GroundType,
+    },
+};
+
+impl Game {
+    /// Grows the grown bush under the drone into a hedge maze filling the
+    /// largest square that fits in the world. Every tile of the maze is
+    /// reachable from every other, and one of them holds the treasure.
+    pub(super) fn spawn_maze(&mut self) -> Result<(), EngineError> {
+        let (x, y) = (self.drone().x(), self.drone().y());
+        let now = self.clock.now();
+        let tile = self.world.get_tile(x, y)?;
+        match tile.entity().map(Entity::entity_type) {
+            None => return Err(EngineError::EntityNotPlanted),
+            Some(EntityType::Bush) => {}
+            Some(_) => return Err(EngineError::ItemNotUsable(Item::WeirdSubstance)),
+        }
+        if !tile.is_grown(now) {
+            return Err(EngineError::EntityNotGrown);
+        }
+        self.inventory.remove(&[(Item::WeirdSubstance, 1)])?;
+
+        let side = self.world.width().min(self.world.height());
+        let origin_x = x.saturating_sub(side / 2).min(self.world.width() - side);
+        let origin_y = y.saturating_sub(side / 2).min(self.world.height() - side);
+        for mx in origin_x..origin_x + side {
+            for my in origin_y..origin_y + side {
+                for direction in Direction::ALL {
+                    self.world.set_wall(mx, my, direction, true);
+                }
+                let tile = self.world.mut_tile(mx, my)?;
+                tile.set_ground_type(GroundType::Grassland);
+                tile.set_entity(Some(Entity::new(EntityType::Hedge, now)))?;
+            }
+        }
+        self.update_all_tree_growth()?;
+
+        // Carve a spanning tree with a randomized depth-first search, working
+        // in maze-local coordinates so the outer walls stay in place.
+        let mut visited = vec![false; side * side];
+        let start = (
+            self.rng.random_range(0..side),
+            self.rng.random_range(0..side),
+        );
+        visited[start.1 * side + start.0] = true;
+        let mut stack = vec![start];
+        while let Some(&(cx, cy)) = stack.last() {
+            let unvisited: Vec<_> = Direction::ALL
+                .into_iter()
+                .filter_map(|direction| {
+                    let (nx, ny) = match direction {
+                        Direction::North => (cx, cy.checked_sub(1)?),
+                        Direction::East => (cx + 1, cy),
+                        Direction::South => (cx, cy + 1),
+                        Direction::West => (cx.checked_sub(1)?, cy),
+                    };
+                    (nx < side && ny < side && !visited[ny * side + nx])
+                        .then_some((direction, nx, ny))
+                })
+                .collect();
+            if unvisited.is_empty() {
+                stack.pop();
+                continue;
+            }
+            let (direction, nx, ny) = unvisited[self.rng.random_range(0..unvisited.len())];
+            self.world
+                .set_wall(origin_x + cx, origin_y + cy, direction, false);
+            visited[ny * side + nx] = true;
+            stack.push((nx, ny));
+        }
+
+        let treasure = (
+            origin_x + self.rng.random_range(0..side),
+            origin_y + self.rng.random_range(0..side),
+        );
+        self.world
+            .mut_tile(treasure.0, treasure.1)?
+            .set_entity(Some(Entity::new(EntityType::Treasure, now)))?;
+        Ok(())
+    }
+
+    /// All hedge and treasure tiles, which vanish once the treasure is found.
+    pub(super) fn maze_region(&self) -> Vec<(usize, usize)> {
+        let mut region = Vec::new();
+        for x in 0..self.world.width() {
+            for y in 0..self.world.height() {
+                let in_maze = self
+                    .world
+                    .get_tile(x, y)
+                    .ok()
+                    .and_then(|tile| tile.entity())
+                    .is_some_and(|entity| {
+                        matches!(
+                            entity.entity_type(),
+                            EntityType::Hedge | EntityType::Treasure
+                        )
+                    });
+                if in_maze {
+                    region.push((x, y));
+                }
+            }
+        }
+        region
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+    use crate::{
+        consts,
+        error::EngineError,
+        game::{inventory::Item, testing::TestGame, Direction, Game},
+        world::entities::EntityType,
+    };
+
+    fn create_maze_game(size: usize) -> Game {
+        let mut game = TestGame::new(size, size)
+            .item(Item::WeirdSubstance, 1)
+            .build();
+        game.plant(EntityType::Bush).unwrap();
+        game.advance_time(EntityType::Bush.growth_time());
+        game.use_item(Item::WeirdSubstance).unwrap();
+        game
+    }
+
+    fn find_treasure(game: &Game) -> (usize, usize) {
+        let world = game.world();
+        (0..world.width())
+            .flat_map(|x| (0..world.height()).map(move |y| (x, y)))
+            .find(|&(x, y)| {
+                world
+                    .get_tile(x, y)
+                    .unwrap()
+                    .entity()
+                    .unwrap()
+                    .entity_type()
+                    == &EntityType::Treasure
+            })
+            .unwrap()
+    }
+
+    /// Directions leading from the drone to `target`, found by breadth-first
+    /// search through the open walls.
+    fn path_to(game: &Game, target: (usize, usize)) -> Option<Vec<Direction>> {
+        let world = game.world();
+        let start = game.drone_position();
+        let mut came_from = HashMap::from([(start, None)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some((x, y)) = queue.pop_front() {
+            for direction in Direction::ALL {
+                if world.has_wall(x, y, direction) {
+                    continue;
+                }
+                let next = world.neighbor(x, y, direction);
+                if let Entry::Vacant(entry) = came_from.entry(next) {
+                    entry.insert(Some(((x, y), direction)));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut path = Vec::new();
+        let mut current = target;
+        while let Some(step) = came_from.get(&current)? {
+            path.push(step.1);
+            current = step.0;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    #[test]
+    fn weird_substance_turns_bush_into_maze_with_one_treasure() {
+        let game = create_maze_game(5);
+
+        let types: Vec<_> = game
+            .world()
+            .tiles()
+            .map(|tile| tile.entity().unwrap().entity_type().clone())
+            .collect();
+        assert_eq!(
+            types.iter().filter(|t| **t == EntityType::Treasure).count(),
+            1
+        );
+        assert_eq!(
+            types.iter().filter(|t| **t == EntityType::Hedge).count(),
+            24
+        );
+        assert_eq!(game.inventory().count(Item::WeirdSubstance), 0);
+    }
+
+    #[test]
+    fn maze_is_perfect() {
+        let game = create_maze_game(6);
+        let world = game.world();
+
+        let mut open_edges = 0;
+        for x in 0..6 {
+            for y in 0..6 {
+                assert!(path_to(&game, (x, y)).is_some());
+                open_edges += [Direction::East, Direction::South]
+                    .into_iter()
+                    .filter(|&direction| !world.has_wall(x, y, direction))
+                    .count();
+            }
+        }
+        assert_eq!(open_edges, 6 * 6 - 1);
+    }
+
+    #[test]
+    fn walls_block_drone_movement() {
+        let mut game = create_maze_game(5);
+        let (x, y) = game.drone_position();
+        let blocked = Direction::ALL
+            .into_iter()
+            .find(|&direction| game.world().has_wall(x, y, direction))
+            .unwrap();
+
+        assert!(!game.can_move(blocked));
+        assert!(matches!(
+            game.move_drone(blocked).unwrap_err(),
+            EngineError::BlockedByWall(direction) if direction == blocked
+        ));
+        assert_eq!(game.drone_position(), (x, y));
+    }
+
+    #[test]
+    fn harvesting_treasure_yields_gold_and_removes_maze() {
+        let mut game = create_maze_game(5);
+        for direction in path_to(&game, find_treasure(&game)).unwrap() {
+            assert!(game.can_move(direction));
+            game.move_drone(direction).unwrap();
+        }
+
+        game.harvest().unwrap();
+        assert_eq!(
+            game.inventory().count(Item::Gold),
+            25 * consts::TREASURE_GOLD_PER_TILE
+        );
+        assert!(game.world().tiles().all(|tile| tile.entity().is_none()));
+        for direction in Direction::ALL {
+            assert!(game.can_move(direction));
+        }
+    }
+
+    #[test]
+    fn hedges_cannot_be_harvested() {
+        let mut game = create_maze_game(5);
+        let (x, y) = game
+            .maze_region()
+            .into_iter()
+            .find(|&position| position != find_treasure(&game))
+            .unwrap();
+        game.mut_drone().set_position(x, y);
+
+        assert!(matches!(
+            game.harvest().unwrap_err(),
+            EngineError::EntityNotGrown
+        ));
+    }
+
+    #[test]
+    fn weird_substance_only_works_on_grown_bushes() {
+        let mut game = TestGame::new(5, 5).item(Item::WeirdSubstance, 1).build();
+
+        assert!(matches!(
+            game.use_item(Item::WeirdSubstance).unwrap_err(),
+            EngineError::EntityNotPlanted
+        ));
+        game.plant(EntityType::Bush).unwrap();
+        assert!(matches!(
+            game.use_item(Item::WeirdSubstance).unwrap_err(),
+            EngineError::EntityNotGrown
+        ));
+        game.plant(EntityType::Tree).unwrap();
+        game.advance_time(EntityType::Tree.growth_time());
+        assert!(matches!(
+            game.use_item(Item::WeirdSubstance).unwrap_err(),
+            EngineError::ItemNotUsable(Item::WeirdSubstance)
+        ));
+        assert_eq!(game.inventory().count(Item::WeirdSubstance), 1);
+    }
+}
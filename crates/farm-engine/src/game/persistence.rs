@@ -0,0 +1,259 @@
+use std::time::Duration;
+
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    error::EngineError,
+    game::{
+        clock::SimulatedClock, cost::OperationCosts, drone::Drone, inventory::Inventory,
+        log::ActionLog, unlock::Unlocks, DroneId, Game,
+    },
+    world::World,
+};
+
+// Both states list the same fields in the same order. Only the time of the
+// clock is kept, so a restored game always runs on a simulated clock.
+#[derive(Serialize)]
+struct GameStateRef<'a> {
+    world: &'a World,
+    drones: &'a [Drone],
+    active_drone: DroneId,
+    max_drones: usize,
+    now: Duration,
+    operation_costs: &'a OperationCosts,
+    elapsed_ticks: u64,
+    inventory: &'a Inventory,
+    rng: &'a ChaCha8Rng,
+    growth_variance: f64,
+    pumpkin_death_chance: f64,
+    powered_operations: u64,
+    unlocks: &'a Unlocks,
+    action_log: Option<&'a ActionLog>,
+}
+
+#[derive(Deserialize)]
+struct GameState {
+    world: World,
+    drones: Vec<Drone>,
+    active_drone: DroneId,
+    max_drones: usize,
+    now: Duration,
+    operation_costs: OperationCosts,
+    elapsed_ticks: u64,
+    inventory: Inventory,
+    rng: ChaCha8Rng,
+    growth_variance: f64,
+    pumpkin_death_chance: f64,
+    powered_operations: u64,
+    unlocks: Unlocks,
+    #[serde(default)]
+    action_log: Option<ActionLog>,
+}
+
+impl Serialize for Game {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GameStateRef {
+            world: &self.world,
+            drones: &self.drones,
+            active_drone: self.active_drone,
+            max_drones: self.max_drones,
+            now: self.now(),
+            operation_costs: &self.operation_costs,
+            elapsed_ticks: self.elapsed_ticks,
+            inventory: &self.inventory,
+            rng: &self.rng,
+            growth_variance: self.growth_variance,
+            pumpkin_death_chance: self.pumpkin_death_chance,
+            powered_operations: self.powered_operations,
+            unlocks: &self.unlocks,
+            action_log: self.action_log.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Game {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = GameState::deserialize(deserializer)?;
+        if state.active_drone.0 >= state.drones.len() {
+            return Err(serde::de::Error::custom(format!(
+                "active drone {:?} does not exist",
+                state.active_drone
+            )));
+        }
+        if state.drones.len() > state.max_drones {
+            return Err(serde::de::Error::custom(format!(
+                "{} drones exceed the limit of {}",
+                state.drones.len(),
+                state.max_drones
+            )));
+        }
+        let (width, height) = (state.world.width(), state.world.height());
+        let in_bounds = |&(x, y): &(usize, usize)| x < width && y < height;
+        for drone in &state.drones {
+            if !in_bounds(&(drone.x(), drone.y())) || !drone.tail().iter().all(in_bounds) {
+                return Err(serde::de::Error::custom(format!(
+                    "drone at ({}, {}) is out of bounds",
+                    drone.x(),
+                    drone.y()
+                )));
+            }
+        }
+        Ok(Self {
+            world: state.world,
+            drones: state.drones,
+            active_drone: state.active_drone,
+            max_drones: state.max_drones,
+            clock: Box::new(SimulatedClock::starting_at(state.now)),
+            operation_costs: state.operation_costs,
+            elapsed_ticks: state.elapsed_ticks,
+            inventory: state.inventory,
+            rng: state.rng,
+            growth_variance: state.growth_variance,
+            pumpkin_death_chance: state.pumpkin_death_chance,
+            powered_operations: state.powered_operations,
+            deferred_ticks: None,
+            unlocks: state.unlocks,
+            action_log: state.action_log,
+            observers: Vec::new(),
+            next_observer_id: 0,
+        })
+    }
+}
+
+impl Game {
+    /// Pretty-printed JSON with map keys in sorted order, so equal states
+    /// produce identical text.
+    pub fn to_json(&self) -> Result<String, EngineError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, EngineError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EngineError> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EngineError> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        consts,
+        error::EngineError,
+        game::{inventory::Item, testing::TestGame, Direction, Game, Hat},
+        world::entities::EntityType,
+    };
+
+    fn create_played_game() -> Game {
+        let mut game = TestGame::new(5, 5)
+            .seed(7)
+            .growth_variance(consts::DEFAULT_GROWTH_VARIANCE)
+            .item(Item::Hay, 10)
+            .item(Item::Wood, 10)
+            .build();
+        game.till().unwrap();
+        game.plant(EntityType::Carrot).unwrap();
+        game.move_drone(Direction::East).unwrap();
+        game.plant(EntityType::Bush).unwrap();
+        game.spawn_drone().unwrap();
+        game.move_drone(Direction::North).unwrap();
+        game.world.set_wall(3, 3, Direction::East, true);
+        game
+    }
+
+    #[test]
+    fn can_round_trip_json() {
+        let game = create_played_game();
+        let json = game.to_json().unwrap();
+        let restored = Game::from_json(&json).unwrap();
+
+        assert_eq!(restored.to_json().unwrap(), json);
+        assert_eq!(restored.now(), game.now());
+        assert_eq!(restored.elapsed_ticks(), game.elapsed_ticks());
+        assert_eq!(restored.drone_positions(), game.drone_positions());
+        assert_eq!(
+            restored.get_world_grid_string(),
+            game.get_world_grid_string()
+        );
+        assert!(restored.world().has_wall(3, 3, Direction::East));
+    }
+
+    #[test]
+    fn can_round_trip_bytes() {
+        let game = create_played_game();
+        let bytes = game.to_bytes().unwrap();
+        let restored = Game::from_bytes(&bytes).unwrap();
+
+        assert_eq!(restored.to_bytes().unwrap(), bytes);
+        assert_eq!(restored.to_json().unwrap(), game.to_json().unwrap());
+        assert!(bytes.len() < game.to_json().unwrap().len());
+    }
+
+    #[test]
+    fn json_is_stable_across_equal_games() {
+        assert_eq!(
+            create_played_game().to_json().unwrap(),
+            create_played_game().to_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn restored_game_continues_identically() {
+        let mut game = create_played_game();
+        let mut restored = Game::from_json(&game.to_json().unwrap()).unwrap();
+
+        for current in [&mut game, &mut restored] {
+            current.advance_time(EntityType::Carrot.growth_time());
+            current
+                .select_drone(current.drone_ids().last().unwrap())
+                .unwrap();
+            current.move_drone(Direction::West).unwrap();
+            current.harvest().unwrap();
+            current.plant(EntityType::Carrot).unwrap();
+            current.change_hat(Hat::Dinosaur).unwrap();
+        }
+
+        assert_eq!(restored.to_json().unwrap(), game.to_json().unwrap());
+    }
+
+    fn tampered_json(edit: impl FnOnce(&mut serde_json::Value)) -> String {
+        let mut state: serde_json::Value =
+            serde_json::from_str(&create_played_game().to_json().unwrap()).unwrap();
+        edit(&mut state);
+        state.to_string()
+    }
+
+    #[test]
+    fn cannot_restore_inconsistent_state() {
+        let tampered = [
+            tampered_json(|state| state["world"]["width"] = 9.into()),
+            tampered_json(|state| state["world"]["height"] = 0.into()),
+            tampered_json(|state| state["world"]["next_tile_id"] = 3.into()),
+            tampered_json(|state| {
+                state["world"]["tiles"].as_object_mut().unwrap().remove("4");
+            }),
+            tampered_json(|state| state["drones"][0]["x"] = 5.into()),
+            tampered_json(|state| state["max_drones"] = 1.into()),
+        ];
+        for json in tampered {
+            assert!(matches!(Game::from_json(&json), Err(EngineError::Json(_))));
+        }
+        assert!(Game::from_json(&tampered_json(|_| {})).is_ok());
+    }
+
+    #[test]
+    fn cannot_restore_invalid_json() {
+        assert!(matches!(Game::from_json("{}"), Err(EngineError::Json(_))));
+        assert!(matches!(
+            Game::from_bytes(&[1, 2, 3]),
+            Err(EngineError::Binary(_))
+        ));
+    }
+}
@@ -0,0 +1,173 @@
+use ndarray::Array2;
+
+use crate::{consts, error::EngineError, game::Game, world::entities::EntityType};
+
+/// Yield multiplier for harvesting a giant pumpkin made of `region_size`
+/// tiles. Grows with the cube of the side length until the side reaches
+/// `GIANT_PUMPKIN_MAX_MULTIPLIER`.
+pub(super) fn giant_pumpkin_multiplier(region_size: usize) -> u64 {
+    let side = region_size.isqrt() as u64;
+    region_size as u64 * side.min(consts::GIANT_PUMPKIN_MAX_MULTIPLIER)
+}
+
+impl Game {
+    fn is_live_grown_pumpkin(&self, x: usize, y: usize) -> Result<bool, EngineError> {
+        let tile = self.world.get_tile(x, y)?;
+        Ok(tile.entity().is_some_and(|entity| {
+            entity.entity_type() == &EntityType::Pumpkin && !entity.withers()
+        }) && tile.is_grown(self.clock.now()))
+    }
+
+    /// The largest square of grown pumpkins containing `(x, y)`, which merges
+    /// into a single giant pumpkin when harvested.
+    pub(super) fn giant_pumpkin_region(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> Result<Vec<(usize, usize)>, EngineError> {
+        if !self.is_live_grown_pumpkin(x, y)? {
+            return Ok(vec![(x, y)]);
+        }
+
+        let (width, height) = (self.world.width(), self.world.height());
+        // Side of the largest pumpkin square whose bottom-right corner is (i, j).
+        let mut sides = Array2::<usize>::zeros((width, height));
+        let mut best = (1, x, y);
+        for i in 0..width {
+            for j in 0..height {
+                if !self.is_live_grown_pumpkin(i, j)? {
+                    continue;
+                }
+                let side = if i == 0 || j == 0 {
+                    1
+                } else {
+                    1 + sides[(i - 1, j)]
+                        .min(sides[(i, j - 1)])
+                        .min(sides[(i - 1, j - 1)])
+                };
+                sides[(i, j)] = side;
+
+                let covers_target = i >= x && j >= y && side > (i - x).max(j - y);
+                if covers_target && side > best.0 {
+                    best = (side, i, j);
+                }
+            }
+        }
+
+        let (side, i, j) = best;
+        let mut region = Vec::with_capacity(side * side);
+        for rx in i + 1 - side..=i {
+            for ry in j + 1 - side..=j {
+                region.push((rx, ry));
+            }
+        }
+        Ok(region)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::{inventory::Item, testing::TestGame, Direction},
+        world::{entities::Entity, tiles::GroundType},
+    };
+
+    fn create_pumpkin_game(pumpkins: &[(usize, usize)]) -> Game {
+        let mut game = TestGame::new(5, 5).build();
+        for &(x, y) in pumpkins {
+            let entity = Entity::new(EntityType::Pumpkin, game.now());
+            let tile = game.world.mut_tile(x, y).unwrap();
+            tile.set_ground_type(GroundType::Soil);
+            tile.set_entity(Some(entity)).unwrap();
+        }
+        game.advance_time(EntityType::Pumpkin.growth_time());
+        game
+    }
+
+    #[test]
+    fn multiplier_is_superlinear_in_side_length() {
+        assert_eq!(giant_pumpkin_multiplier(1), 1);
+        assert_eq!(giant_pumpkin_multiplier(4), 8);
+        assert_eq!(giant_pumpkin_multiplier(9), 27);
+        assert_eq!(giant_pumpkin_multiplier(100), 600);
+    }
+
+    #[test]
+    fn square_of_grown_pumpkins_is_harvested_as_one_giant_pumpkin() {
+        let mut game = create_pumpkin_game(&[(0, 0), (1, 0), (0, 1), (1, 1), (2, 0)]);
+
+        game.harvest().unwrap();
+        assert_eq!(
+            game.inventory().count(Item::Pumpkin),
+            8 * consts::PUMPKIN_YIELD
+        );
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            assert!(game.world().get_tile(x, y).unwrap().entity().is_none());
+        }
+        assert!(game.world().get_tile(2, 0).unwrap().entity().is_some());
+    }
+
+    #[test]
+    fn harvesting_any_tile_of_a_giant_pumpkin_harvests_the_whole_square() {
+        let mut game = create_pumpkin_game(&[(1, 1), (2, 1), (1, 2), (2, 2)]);
+
+        game.move_drone(Direction::East).unwrap();
+        game.move_drone(Direction::East).unwrap();
+        game.move_drone(Direction::South).unwrap();
+        game.move_drone(Direction::South).unwrap();
+        game.harvest().unwrap();
+        assert_eq!(
+            game.inventory().count(Item::Pumpkin),
+            8 * consts::PUMPKIN_YIELD
+        );
+    }
+
+    #[test]
+    fn lone_pumpkin_yields_single_pumpkin() {
+        let mut game = create_pumpkin_game(&[(0, 0), (2, 0)]);
+
+        game.harvest().unwrap();
+        assert_eq!(game.inventory().count(Item::Pumpkin), consts::PUMPKIN_YIELD);
+    }
+
+    #[test]
+    fn pumpkins_may_die_and_must_be_replanted() {
+        let mut game = TestGame::new(3, 3)
+            .pumpkin_death_chance(1.0)
+            .item(Item::Carrot, 2)
+            .build();
+
+        game.till().unwrap();
+        game.plant(EntityType::Pumpkin).unwrap();
+        assert_eq!(game.get_entity_type().unwrap(), Some(&EntityType::Pumpkin));
+
+        game.advance_time(EntityType::Pumpkin.growth_time());
+        assert_eq!(
+            game.get_entity_type().unwrap(),
+            Some(&EntityType::DeadPumpkin)
+        );
+        assert!(!game.can_harvest().unwrap());
+        assert!(matches!(
+            game.harvest().unwrap_err(),
+            EngineError::EntityNotGrown
+        ));
+
+        game.plant(EntityType::Pumpkin).unwrap();
+        assert_eq!(game.get_entity_type().unwrap(), Some(&EntityType::Pumpkin));
+    }
+
+    #[test]
+    fn dying_pumpkin_breaks_giant_pumpkin() {
+        let mut game = create_pumpkin_game(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        game.world
+            .mut_tile(1, 1)
+            .unwrap()
+            .mut_entity()
+            .unwrap()
+            .set_withers(true);
+
+        game.harvest().unwrap();
+        assert_eq!(game.inventory().count(Item::Pumpkin), consts::PUMPKIN_YIELD);
+    }
+}
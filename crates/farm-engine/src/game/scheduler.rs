@@ -0,0 +1,256 @@
+use std::collections::{HashMap, VecDeque};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    error::EngineError,
+    game::{action::Action, DroneId, Game},
+};
+
+/// What happens when two drones act on the same tile in the same tick.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ConflictPolicy {
+    /// Both actions are performed, one after the other.
+    #[default]
+    Ordered,
+    /// Only the first action is performed. The later one fails with
+    /// `EngineError::TileConflict` without costing any ticks.
+    Error,
+}
+
+/// Outcome of one action run by the scheduler.
+#[derive(Debug)]
+pub struct ScheduledAction {
+    pub drone: DroneId,
+    pub tick: u64,
+    pub action: Action,
+    pub result: Result<(), EngineError>,
+}
+
+/// Runs queued actions of several drones in parallel. Every drone starts its
+/// next action as soon as its previous one has paid off its tick cost.
+/// Drones starting in the same tick go in an order shuffled by the seed, so
+/// runs are replayable.
+pub struct Scheduler {
+    queues: Vec<(DroneId, VecDeque<Action>)>,
+    conflict_policy: ConflictPolicy,
+    rng: ChaCha8Rng,
+}
+
+impl Scheduler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            queues: Vec::new(),
+            conflict_policy: ConflictPolicy::default(),
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn conflict_policy(mut self, conflict_policy: ConflictPolicy) -> Self {
+        self.conflict_policy = conflict_policy;
+        self
+    }
+
+    pub fn push(&mut self, drone: DroneId, action: Action) -> &mut Self {
+        match self.queues.iter_mut().find(|(id, _)| *id == drone) {
+            Some((_, queue)) => queue.push_back(action),
+            None => self.queues.push((drone, VecDeque::from([action]))),
+        }
+        self
+    }
+
+    /// Runs every queued action and leaves the game clock at the tick the
+    /// last drone finishes.
+    pub fn run(&mut self, game: &mut Game) -> Result<Vec<ScheduledAction>, EngineError> {
+        let start = game.elapsed_ticks();
+        let mut ready_at: HashMap<DroneId, u64> = self
+            .queues
+            .iter()
+            .map(|&(drone, _)| (drone, start))
+            .collect();
+        let mut touched: HashMap<(usize, usize), DroneId> = HashMap::new();
+        let mut touched_tick = start;
+        let mut records = Vec::new();
+
+        while let Some(tick) = self
+            .queues
+            .iter()
+            .filter(|(_, queue)| !queue.is_empty())
+            .map(|(drone, _)| ready_at[drone])
+            .min()
+        {
+            if tick != touched_tick {
+                touched.clear();
+                touched_tick = tick;
+            }
+            game.advance_to_tick(tick);
+
+            let mut drones: Vec<_> = self
+                .queues
+                .iter()
+                .filter(|(drone, queue)| !queue.is_empty() && ready_at[drone] == tick)
+                .map(|&(drone, _)| drone)
+                .collect();
+            drones.shuffle(&mut self.rng);
+
+            for drone in drones {
+                let Some(action) = self
+                    .queues
+                    .iter_mut()
+                    .find(|(id, _)| *id == drone)
+                    .and_then(|(_, queue)| queue.pop_front())
+                else {
+                    continue;
+                };
+                let targets = game.with_drone(drone, |game| action.target_tiles(game))?;
+                let conflict = targets
+                    .iter()
+                    .find(|tile| touched.get(tile).is_some_and(|&other| other != drone));
+                let result = match (conflict, self.conflict_policy) {
+                    (Some(&(x, y)), ConflictPolicy::Error) => {
+                        Err(EngineError::TileConflict { x, y })
+                    }
+                    _ => {
+                        let (result, ticks) = game
+                            .run_deferred(|game| game.with_drone(drone, |game| action.apply(game)));
+                        if let Some(ready) = ready_at.get_mut(&drone) {
+                            *ready += ticks;
+                        }
+                        for tile in targets {
+                            touched.insert(tile, drone);
+                        }
+                        result?
+                    }
+                };
+                records.push(ScheduledAction {
+                    drone,
+                    tick,
+                    action,
+                    result,
+                });
+            }
+        }
+
+        game.advance_to_tick(ready_at.values().copied().max().unwrap_or(start));
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        consts,
+        game::{testing::TestGame, Direction},
+        world::entities::EntityType,
+    };
+
+    #[test]
+    fn drones_act_in_parallel() {
+        let mut game = TestGame::new(5, 5).drones(2).build();
+        let drones: Vec<_> = game.drone_ids().collect();
+        let start = game.elapsed_ticks();
+        let mut scheduler = Scheduler::new(0);
+        for _ in 0..3 {
+            scheduler.push(drones[0], Action::Move(Direction::East));
+            scheduler.push(drones[1], Action::Move(Direction::South));
+        }
+
+        let records = scheduler.run(&mut game).unwrap();
+        assert_eq!(records.len(), 6);
+        assert!(records.iter().all(|record| record.result.is_ok()));
+        assert_eq!(game.elapsed_ticks(), start + 3 * consts::MOVE_TICKS);
+        assert_eq!(game.drone_positions(), vec![(3, 0), (0, 3)]);
+    }
+
+    #[test]
+    fn actions_start_when_previous_action_is_paid_off() {
+        let mut game = TestGame::new(5, 5).drones(2).build();
+        let drones: Vec<_> = game.drone_ids().collect();
+        let start = game.elapsed_ticks();
+        let mut scheduler = Scheduler::new(0);
+        scheduler
+            .push(drones[0], Action::Plant(EntityType::Bush))
+            .push(drones[0], Action::Harvest);
+        scheduler.push(drones[1], Action::Move(Direction::East));
+
+        let records = scheduler.run(&mut game).unwrap();
+        let harvest = records
+            .iter()
+            .find(|record| record.action == Action::Harvest)
+            .unwrap();
+        assert_eq!(harvest.tick, start + consts::PLANT_TICKS);
+        assert!(matches!(harvest.result, Err(EngineError::EntityNotGrown)));
+    }
+
+    #[test]
+    fn ordered_conflicts_perform_both_actions() {
+        let mut game = TestGame::new(5, 5).drones(2).build();
+        let drones: Vec<_> = game.drone_ids().collect();
+        let mut scheduler = Scheduler::new(0);
+        scheduler.push(drones[0], Action::Plant(EntityType::Bush));
+        scheduler.push(drones[1], Action::Plant(EntityType::Grass));
+
+        let records = scheduler.run(&mut game).unwrap();
+        assert!(records.iter().all(|record| record.result.is_ok()));
+        let Action::Plant(last) = &records[1].action else {
+            panic!("expected a plant action");
+        };
+        assert_eq!(game.get_entity_type().unwrap(), Some(last));
+    }
+
+    #[test]
+    fn conflicts_can_be_rejected() {
+        let mut game = TestGame::new(5, 5).drones(2).build();
+        let drones: Vec<_> = game.drone_ids().collect();
+        let start = game.elapsed_ticks();
+        let mut scheduler = Scheduler::new(0).conflict_policy(ConflictPolicy::Error);
+        scheduler.push(drones[0], Action::Plant(EntityType::Bush));
+        scheduler.push(drones[1], Action::Plant(EntityType::Bush));
+
+        let records = scheduler.run(&mut game).unwrap();
+        assert!(records[0].result.is_ok());
+        assert!(matches!(
+            records[1].result,
+            Err(EngineError::TileConflict { x: 0, y: 0 })
+        ));
+        assert_eq!(game.elapsed_ticks(), start + consts::PLANT_TICKS);
+    }
+
+    #[test]
+    fn drones_on_different_tiles_do_not_conflict() {
+        let mut game = TestGame::new(5, 5).drones(2).build();
+        let drones: Vec<_> = game.drone_ids().collect();
+        game.with_drone(drones[1], |game| game.move_drone(Direction::East))
+            .unwrap()
+            .unwrap();
+        let mut scheduler = Scheduler::new(0).conflict_policy(ConflictPolicy::Error);
+        scheduler.push(drones[0], Action::Plant(EntityType::Bush));
+        scheduler.push(drones[1], Action::Plant(EntityType::Bush));
+
+        let records = scheduler.run(&mut game).unwrap();
+        assert!(records.iter().all(|record| record.result.is_ok()));
+    }
+
+    fn run_order(seed: u64) -> Vec<DroneId> {
+        let mut game = TestGame::new(5, 5).drones(8).build();
+        let drones: Vec<_> = game.drone_ids().collect();
+        let mut scheduler = Scheduler::new(seed);
+        for &drone in &drones {
+            scheduler.push(drone, Action::Move(Direction::East));
+        }
+        scheduler
+            .run(&mut game)
+            .unwrap()
+            .into_iter()
+            .map(|record| record.drone)
+            .collect()
+    }
+
+    #[test]
+    fn tie_order_is_deterministic_under_seed() {
+        assert_eq!(run_order(7), run_order(7));
+        assert_ne!(run_order(7), run_order(8));
+    }
+}
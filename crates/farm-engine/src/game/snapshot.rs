@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    game::{drone::Drone, inventory::Inventory, log::ActionLog, unlock::Unlocks, DroneId, Game},
+    world::World,
+};
+
+/// Saved state of a `Game`, taken with `Game::snapshot`. Snapshots share
+/// unchanged tiles with the game and with each other, so taking one is cheap
+/// even for large worlds.
+#[derive(Clone)]
+pub struct Snapshot {
+    world: World,
+    drones: Vec<Drone>,
+    active_drone: DroneId,
+    now: Duration,
+    elapsed_ticks: u64,
+    inventory: Inventory,
+    rng: ChaCha8Rng,
+    powered_operations: u64,
+    unlocks: Unlocks,
+    logged_actions: Option<usize>,
+}
+
+impl Snapshot {
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    pub fn elapsed_ticks(&self) -> u64 {
+        self.elapsed_ticks
+    }
+}
+
+impl Game {
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            world: self.world.clone(),
+            drones: self.drones.clone(),
+            active_drone: self.active_drone,
+            now: self.now(),
+            elapsed_ticks: self.elapsed_ticks,
+            inventory: self.inventory.clone(),
+            rng: self.rng.clone(),
+            powered_operations: self.powered_operations,
+            unlocks: self.unlocks.clone(),
+            logged_actions: self.action_log.as_ref().map(ActionLog::len),
+        }
+    }
+
+    /// Rolls the game back, or forward, to `snapshot`, including the clock.
+    /// Actions recorded since the snapshot are dropped from the action log.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.world = snapshot.world.clone();
+        self.drones = snapshot.drones.clone();
+        self.active_drone = snapshot.active_drone;
+        self.clock.set_now(snapshot.now);
+        self.elapsed_ticks = snapshot.elapsed_ticks;
+        self.inventory = snapshot.inventory.clone();
+        self.rng = snapshot.rng.clone();
+        self.powered_operations = snapshot.powered_operations;
+        self.unlocks = snapshot.unlocks.clone();
+        if let (Some(log), Some(len)) = (self.action_log.as_mut(), snapshot.logged_actions) {
+            log.truncate(len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        consts,
+        game::{inventory::Item, log::Replay, testing::TestGame, unlock::Unlock, Direction, Game},
+        world::entities::EntityType,
+    };
+
+    #[test]
+    fn can_restore_snapshot() {
+        let mut game = TestGame::new(4, 4)
+            .seed(3)
+            .growth_variance(consts::DEFAULT_GROWTH_VARIANCE)
+            .build();
+        game.plant(EntityType::Bush).unwrap();
+        let snapshot = game.snapshot();
+        let grid = game.get_world_grid_string();
+
+        game.advance_time(EntityType::Bush.growth_time());
+        game.harvest().unwrap();
+        game.move_drone(Direction::North).unwrap();
+        game.spawn_drone().unwrap();
+        game.restore(&snapshot);
+
+        assert_eq!(game.get_world_grid_string(), grid);
+        assert_eq!(game.now(), snapshot.now());
+        assert_eq!(game.elapsed_ticks(), snapshot.elapsed_ticks());
+        assert_eq!(game.drone_positions(), vec![(0, 0)]);
+        assert_eq!(game.inventory().count(Item::Wood), 0);
+        assert!(!game.can_harvest().unwrap());
+    }
+
+    #[test]
+    fn restored_branches_replay_identically() {
+        let mut game = TestGame::new(4, 4)
+            .seed(3)
+            .growth_variance(consts::DEFAULT_GROWTH_VARIANCE)
+            .build();
+        let snapshot = game.snapshot();
+
+        let branch = |game: &mut Game| {
+            game.restore(&snapshot);
+            game.plant(EntityType::Tree).unwrap();
+            game.move_drone(Direction::East).unwrap();
+            game.plant(EntityType::Bush).unwrap();
+            let world = game.world();
+            (
+                world.get_tile(0, 0).unwrap().clone(),
+                world.get_tile(1, 0).unwrap().clone(),
+                game.elapsed_ticks(),
+            )
+        };
+
+        assert_eq!(branch(&mut game), branch(&mut game));
+    }
+
+    #[test]
+    fn restoring_drops_actions_of_abandoned_branch() {
+        let test_game = TestGame::new(4, 4).record_actions();
+        let mut game = test_game.build();
+        game.move_drone(Direction::East).unwrap();
+        let snapshot = game.snapshot();
+        game.move_drone(Direction::East).unwrap();
+        game.restore(&snapshot);
+        game.move_drone(Direction::South).unwrap();
+
+        let log = game.take_action_log().unwrap();
+        assert_eq!(log.len(), 2);
+        Replay::new(log).run(&mut test_game.build()).unwrap();
+    }
+
+    #[test]
+    fn snapshot_is_unaffected_by_later_changes() {
+        let mut game = TestGame::new(4, 4).locked().item(Item::Hay, 100).build();
+        let snapshot = game.snapshot();
+
+        game.till().unwrap_err();
+        game.unlock(Unlock::Till).unwrap();
+        game.till().unwrap();
+        let now = game.now();
+        game.world.mut_tile(1, 1).unwrap().set_water(1.0, now);
+        game.restore(&snapshot);
+
+        assert!(game.till().is_err());
+        assert_eq!(game.inventory().count(Item::Hay), 100);
+        assert_eq!(game.world().get_tile(1, 1).unwrap().water(game.now()), 0.0);
+    }
+}
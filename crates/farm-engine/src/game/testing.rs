@@ -0,0 +1,93 @@
+use crate::{
+    consts,
+    game::{inventory::Item, unlock::Unlocks, Game, GameOptions},
+};
+
+/// Builds the games used in tests. Unlike `GameOptions::default()`, growth
+/// times are exact and pumpkins never die unless asked for.
+#[derive(Clone)]
+pub(crate) struct TestGame {
+    width: usize,
+    height: usize,
+    seed: u64,
+    growth_variance: f64,
+    pumpkin_death_chance: f64,
+    unlocks: Unlocks,
+    record_actions: bool,
+    items: Vec<(Item, u64)>,
+    drones: usize,
+}
+
+impl TestGame {
+    pub(crate) fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            seed: consts::DEFAULT_SEED,
+            growth_variance: 0.0,
+            pumpkin_death_chance: 0.0,
+            unlocks: Unlocks::all(),
+            record_actions: false,
+            items: Vec::new(),
+            drones: 1,
+        }
+    }
+
+    pub(crate) fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub(crate) fn growth_variance(mut self, growth_variance: f64) -> Self {
+        self.growth_variance = growth_variance;
+        self
+    }
+
+    pub(crate) fn pumpkin_death_chance(mut self, pumpkin_death_chance: f64) -> Self {
+        self.pumpkin_death_chance = pumpkin_death_chance;
+        self
+    }
+
+    /// Starts without any unlocks, like a run from scratch.
+    pub(crate) fn locked(mut self) -> Self {
+        self.unlocks = Unlocks::new();
+        self
+    }
+
+    pub(crate) fn record_actions(mut self) -> Self {
+        self.record_actions = true;
+        self
+    }
+
+    /// Starts with `amount` of `item` in the inventory, without logging it.
+    pub(crate) fn item(mut self, item: Item, amount: u64) -> Self {
+        self.items.push((item, amount));
+        self
+    }
+
+    /// Spawns drones until there are `drones` of them, all at the origin.
+    pub(crate) fn drones(mut self, drones: usize) -> Self {
+        self.drones = drones;
+        self
+    }
+
+    pub(crate) fn build(&self) -> Game {
+        let mut game = Game::new(GameOptions {
+            world_width: self.width,
+            world_height: self.height,
+            seed: self.seed,
+            growth_variance: self.growth_variance,
+            pumpkin_death_chance: self.pumpkin_death_chance,
+            unlocks: self.unlocks.clone(),
+            record_actions: self.record_actions,
+            ..Default::default()
+        });
+        for &(item, amount) in &self.items {
+            game.mut_inventory().add(item, amount);
+        }
+        for _ in 1..self.drones {
+            game.spawn_drone().unwrap();
+        }
+        game
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{
+    consts,
+    error::EngineError,
+    game::{Direction, Game},
+    world::entities::EntityType,
+};
+
+impl Game {
+    fn is_tree_at(&self, x: usize, y: usize) -> Result<bool, EngineError> {
+        Ok(self
+            .world
+            .get_tile(x, y)?
+            .entity()
+            .is_some_and(|entity| entity.entity_type() == &EntityType::Tree))
+    }
+
+    /// Trees grow slower for every orthogonally adjacent tree, wrapping
+    /// around the world edges like the drone does. In worlds one or two
+    /// tiles across, a tile neighbouring from both sides only counts once.
+    fn tree_growth_multiplier(&self, x: usize, y: usize) -> Result<f64, EngineError> {
+        let mut neighbors: Vec<_> = Direction::ALL
+            .iter()
+            .map(|&direction| self.world.neighbor(x, y, direction))
+            .filter(|&position| position != (x, y))
+            .collect();
+        neighbors.sort();
+        neighbors.dedup();
+        let mut adjacent = 0;
+        for (nx, ny) in neighbors {
+            if self.is_tree_at(nx, ny)? {
+                adjacent += 1;
+            }
+        }
+        Ok(consts::TREE_ADJACENT_GROWTH_MULTIPLIER.powi(adjacent))
+    }
+
+    /// Recomputes the growth time of the tree at `(x, y)` and of the trees
+    /// around it. Must be called whenever the entity at `(x, y)` changes.
+    pub(super) fn update_tree_growth_around(
+        &mut self,
+        x: usize,
+        y: usize,
+    ) -> Result<(), EngineError> {
+        let mut positions = vec![(x, y)];
+        positions.extend(Direction::ALL.map(|direction| self.world.neighbor(x, y, direction)));
+        for (tx, ty) in positions {
+            self.update_tree_growth_at(tx, ty)?;
+        }
+        Ok(())
+    }
+
+    /// Recomputes the growth time of every tree, for when many entities or
+    /// the world's neighbours change at once.
+    pub(super) fn update_all_tree_growth(&mut self) -> Result<(), EngineError> {
+        for x in 0..self.world.width() {
+            for y in 0..self.world.height() {
+                self.update_tree_growth_at(x, y)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn update_tree_growth_at(&mut self, x: usize, y: usize) -> Result<(), EngineError> {
+        if !self.is_tree_at(x, y)? {
+            return Ok(());
+        }
+        let multiplier = self.tree_growth_multiplier(x, y)?;
+        if let Some(entity) = self.world.mut_tile(x, y)?.mut_entity() {
+            entity.set_growth_multiplier(multiplier);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        consts,
+        game::{inventory::Item, testing::TestGame, Direction, Game, GameOptions},
+        world::{entities::EntityType, generator::WorldGenerator},
+    };
+
+    #[test]
+    fn lone_tree_grows_at_base_speed() {
+        let mut game = TestGame::new(5, 1).build();
+        game.plant(EntityType::Tree).unwrap();
+
+        let tree = game.world().get_tile(0, 0).unwrap().entity().unwrap();
+        assert_eq!(tree.growth_time(), EntityType::Tree.growth_time());
+    }
+
+    #[test]
+    fn adjacent_trees_slow_each_other_down() {
+        let mut game = TestGame::new(5, 1).build();
+        game.plant(EntityType::Tree).unwrap();
+        game.move_drone(Direction::East).unwrap();
+        game.plant(EntityType::Tree).unwrap();
+
+        let slowed = EntityType::Tree
+            .growth_time()
+            .mul_f64(consts::TREE_ADJACENT_GROWTH_MULTIPLIER);
+        for x in 0..2 {
+            let tree = game.world().get_tile(x, 0).unwrap().entity().unwrap();
+            assert_eq!(tree.growth_time(), slowed);
+        }
+    }
+
+    #[test]
+    fn adjacency_wraps_around_world_edges() {
+        let mut game = TestGame::new(5, 1).build();
+        game.plant(EntityType::Tree).unwrap();
+        game.move_drone(Direction::West).unwrap();
+        game.plant(EntityType::Tree).unwrap();
+
+        let tree = game.world().get_tile(0, 0).unwrap().entity().unwrap();
+        assert!(tree.growth_time() > EntityType::Tree.growth_time());
+    }
+
+    #[test]
+    fn tree_neighbouring_from_both_sides_counts_once() {
+        let mut game = TestGame::new(2, 1).build();
+        game.plant(EntityType::Tree).unwrap();
+        game.move_drone(Direction::East).unwrap();
+        game.plant(EntityType::Tree).unwrap();
+
+        let slowed = EntityType::Tree
+            .growth_time()
+            .mul_f64(consts::TREE_ADJACENT_GROWTH_MULTIPLIER);
+        for x in 0..2 {
+            let tree = game.world().get_tile(x, 0).unwrap().entity().unwrap();
+            assert_eq!(tree.growth_time(), slowed);
+        }
+    }
+
+    #[test]
+    fn penalty_is_lifted_when_neighbor_is_harvested() {
+        let mut game = TestGame::new(5, 1).build();
+        game.plant(EntityType::Tree).unwrap();
+        game.move_drone(Direction::East).unwrap();
+        game.plant(EntityType::Tree).unwrap();
+
+        game.advance_time(
+            EntityType::Tree
+                .growth_time()
+                .mul_f64(consts::TREE_ADJACENT_GROWTH_MULTIPLIER),
+        );
+        game.harvest().unwrap();
+
+        let tree = game.world().get_tile(0, 0).unwrap().entity().unwrap();
+        assert_eq!(tree.growth_time(), EntityType::Tree.growth_time());
+    }
+
+    #[test]
+    fn trees_of_a_taken_over_world_slow_each_other_down() {
+        let world = WorldGenerator::new(0)
+            .size(4, 1)
+            .planted_ratio(1.0)
+            .entity_weights(vec![(EntityType::Tree, 1.0)])
+            .unwrap()
+            .growth_variance(0.0)
+            .generate()
+            .unwrap();
+        let generated = Game::with_world(GameOptions::default(), world);
+        let parsed = Game::from_grid_str(GameOptions::default(), "TTT").unwrap();
+
+        let slowed = EntityType::Tree
+            .growth_time()
+            .mul_f64(consts::TREE_ADJACENT_GROWTH_MULTIPLIER.powi(2));
+        for game in [generated, parsed] {
+            let tree = game.world().get_tile(1, 0).unwrap().entity().unwrap();
+            assert_eq!(tree.growth_time(), slowed);
+        }
+    }
+
+    #[test]
+    fn resizing_recomputes_wrapped_neighbours() {
+        let mut game = Game::from_grid_str(GameOptions::default(), "T.T").unwrap();
+        let tree = game.world().get_tile(0, 0).unwrap().entity().unwrap();
+        assert!(tree.growth_time() > EntityType::Tree.growth_time());
+
+        game.resize_world(4, 1).unwrap();
+        let tree = game.world().get_tile(0, 0).unwrap().entity().unwrap();
+        assert_eq!(tree.growth_time(), EntityType::Tree.growth_time());
+    }
+
+    #[test]
+    fn trees_replaced_by_a_maze_stop_slowing_neighbours() {
+        let mut game =
+            Game::from_grid_str(GameOptions::default(), "....\nBTT.\n@0,0 grown").unwrap();
+        game.mut_inventory().add(Item::WeirdSubstance, 1);
+        game.use_item(Item::WeirdSubstance).unwrap();
+
+        assert_eq!(
+            game.world()
+                .get_tile(1, 0)
+                .unwrap()
+                .entity()
+                .unwrap()
+                .entity_type(),
+            &EntityType::Hedge
+        );
+        let tree = game.world().get_tile(2, 0).unwrap().entity().unwrap();
+        assert_eq!(tree.growth_time(), EntityType::Tree.growth_time());
+    }
+
+    #[test]
+    fn checkerboard_trees_are_not_slowed() {
+        let mut game = TestGame::new(4, 1).build();
+        for _ in 0..2 {
+            game.plant(EntityType::Tree).unwrap();
+            game.move_drone(Direction::East).unwrap();
+            game.move_drone(Direction::East).unwrap();
+        }
+
+        for x in [0, 2] {
+            let tree = game.world().get_tile(x, 0).unwrap().entity().unwrap();
+            assert_eq!(tree.growth_time(), EntityType::Tree.growth_time());
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use crate::{consts, game::inventory::Item, world::entities::EntityType};
+
+/// A purchasable feature. Most unlocks are gates that are bought once, while
+/// `Speed` and `Expand` are upgrades that can be bought several times.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unlock {
+    Speed,
+    Expand,
+    Till,
+    Watering,
+    Fertilizer,
+    Swap,
+    Measure,
+    Carrots,
+    Trees,
+    Pumpkins,
+    Sunflowers,
+    Cactus,
+    Polyculture,
+    Mazes,
+    Dinosaur,
+}
+
+impl Unlock {
+    pub const ALL: [Unlock; 15] = [
+        Unlock::Speed,
+        Unlock::Expand,
+        Unlock::Till,
+        Unlock::Watering,
+        Unlock::Fertilizer,
+        Unlock::Swap,
+        Unlock::Measure,
+        Unlock::Carrots,
+        Unlock::Trees,
+        Unlock::Pumpkins,
+        Unlock::Sunflowers,
+        Unlock::Cactus,
+        Unlock::Polyculture,
+        Unlock::Mazes,
+        Unlock::Dinosaur,
+    ];
+
+    /// The unlock needed to plant `entity_type`, if any.
+    pub fn for_entity(entity_type: &EntityType) -> Option<Unlock> {
+        match entity_type {
+            EntityType::Carrot => Some(Unlock::Carrots),
+            EntityType::Tree => Some(Unlock::Trees),
+            EntityType::Pumpkin => Some(Unlock::Pumpkins),
+            EntityType::Sunflower => Some(Unlock::Sunflowers),
+            EntityType::Cactus => Some(Unlock::Cactus),
+            _ => None,
+        }
+    }
+
+    pub fn max_level(&self) -> u32 {
+        match self {
+            Unlock::Speed => consts::SPEED_MAX_LEVEL,
+            Unlock::Expand => consts::EXPAND_WORLD_SIZES.len() as u32,
+            _ => 1,
+        }
+    }
+
+    fn base_cost(&self) -> &'static [(Item, u64)] {
+        match self {
+            Unlock::Speed => &[(Item::Hay, consts::SPEED_UNLOCK_HAY_COST)],
+            Unlock::Expand => &[(Item::Wood, consts::EXPAND_UNLOCK_WOOD_COST)],
+            Unlock::Till => &[(Item::Hay, consts::TILL_UNLOCK_HAY_COST)],
+            Unlock::Watering => &[(Item::Wood, consts::WATERING_UNLOCK_WOOD_COST)],
+            Unlock::Fertilizer => &[(Item::Carrot, consts::FERTILIZER_UNLOCK_CARROT_COST)],
+            Unlock::Swap => &[(Item::Wood, consts::SWAP_UNLOCK_WOOD_COST)],
+            Unlock::Measure => &[(Item::Hay, consts::MEASURE_UNLOCK_HAY_COST)],
+            Unlock::Carrots => &[(Item::Wood, consts::CARROTS_UNLOCK_WOOD_COST)],
+            Unlock::Trees => &[(Item::Wood, consts::TREES_UNLOCK_WOOD_COST)],
+            Unlock::Pumpkins => &[(Item::Carrot, consts::PUMPKINS_UNLOCK_CARROT_COST)],
+            Unlock::Sunflowers => &[(Item::Carrot, consts::SUNFLOWERS_UNLOCK_CARROT_COST)],
+            Unlock::Cactus => &[(Item::Pumpkin, consts::CACTUS_UNLOCK_PUMPKIN_COST)],
+            Unlock::Polyculture => &[(Item::Hay, consts::POLYCULTURE_UNLOCK_HAY_COST)],
+            Unlock::Mazes => &[(Item::Pumpkin, consts::MAZES_UNLOCK_PUMPKIN_COST)],
+            Unlock::Dinosaur => &[(Item::Cactus, consts::DINOSAUR_UNLOCK_CACTUS_COST)],
+        }
+    }
+
+    /// Price of buying the unlock when `level` of it is already owned. Each
+    /// level of an upgrade costs `UNLOCK_COST_GROWTH` times the previous one.
+    pub fn cost(&self, level: u32) -> Vec<(Item, u64)> {
+        let scale = consts::UNLOCK_COST_GROWTH.pow(level);
+        self.base_cost()
+            .iter()
+            .map(|&(item, amount)| (item, amount * scale))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Unlocks {
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_util::sorted_map")
+    )]
+    levels: HashMap<Unlock, u32>,
+}
+
+impl Unlocks {
+    /// Nothing unlocked, as at the start of a run.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every gate open, with upgrades left at their base level.
+    pub fn all() -> Self {
+        let mut unlocks = Self::new();
+        for unlock in Unlock::ALL {
+            if unlock.max_level() == 1 {
+                unlocks.set_level(unlock, 1);
+            }
+        }
+        unlocks
+    }
+
+    pub fn level(&self, unlock: Unlock) -> u32 {
+        self.levels.get(&unlock).copied().unwrap_or_default()
+    }
+
+    pub fn set_level(&mut self, unlock: Unlock, level: u32) -> &mut Self {
+        self.levels.insert(unlock, level.min(unlock.max_level()));
+        self
+    }
+
+    pub fn is_unlocked(&self, unlock: Unlock) -> bool {
+        self.level(unlock) > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_opens_gates_but_not_upgrades() {
+        let unlocks = Unlocks::all();
+        assert!(unlocks.is_unlocked(Unlock::Till));
+        assert!(unlocks.is_unlocked(Unlock::Dinosaur));
+        assert_eq!(unlocks.level(Unlock::Speed), 0);
+        assert_eq!(unlocks.level(Unlock::Expand), 0);
+    }
+
+    #[test]
+    fn upgrade_costs_grow_with_level() {
+        assert_eq!(
+            Unlock::Speed.cost(0),
+            vec![(Item::Hay, consts::SPEED_UNLOCK_HAY_COST)]
+        );
+        assert_eq!(
+            Unlock::Speed.cost(2),
+            vec![(
+                Item::Hay,
+                consts::SPEED_UNLOCK_HAY_COST * consts::UNLOCK_COST_GROWTH.pow(2)
+            )]
+        );
+    }
+
+    #[test]
+    fn levels_are_capped() {
+        let mut unlocks = Unlocks::new();
+        unlocks.set_level(Unlock::Till, 3);
+        assert_eq!(unlocks.level(Unlock::Till), 1);
+    }
+}
@@ -2,4 +2,6 @@ pub(crate) mod consts;
 pub mod display;
 pub mod error;
 pub mod game;
+#[cfg(feature = "serde")]
+mod serde_util;
 pub mod world;
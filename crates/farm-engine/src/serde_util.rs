@@ -0,0 +1,29 @@
+//! Serializes hash-based collections in key order so the same state always
+//! produces the same output.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Serialize, Serializer};
+
+pub(crate) fn sorted_map<'a, M, K, V, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+where
+    &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: Ord + Serialize + 'a,
+    V: Serialize + 'a,
+    S: Serializer,
+{
+    map.into_iter()
+        .collect::<BTreeMap<_, _>>()
+        .serialize(serializer)
+}
+
+pub(crate) fn sorted_set<'a, C, T, S>(set: &'a C, serializer: S) -> Result<S::Ok, S::Error>
+where
+    &'a C: IntoIterator<Item = &'a T>,
+    T: Ord + Serialize + 'a,
+    S: Serializer,
+{
+    set.into_iter()
+        .collect::<BTreeSet<_>>()
+        .serialize(serializer)
+}
@@ -1,19 +1,101 @@
+use im::{HashMap, HashSet};
 use ndarray::Array2;
-use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::error::EngineError;
+use crate::game::Direction;
 
+mod ascii;
 pub mod entities;
+pub mod generator;
 pub mod tiles;
 
 use entities::{Entity, EntityType};
 use tiles::{GroundType, Tile, TileBuilder, TileId};
 
+/// Tiles and walls live in persistent collections, and the tile layout is
+/// only replaced on resize, so cloning a world shares them until either copy
+/// changes.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "WorldState"))]
 pub struct World {
+    width: usize,
+    height: usize,
+    tile_refs: Arc<Array2<Option<TileId>>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_util::sorted_map")
+    )]
+    tiles: HashMap<TileId, Tile>,
+    next_tile_id: TileId,
+    // Each wall is keyed by the tile to its west or north.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_util::sorted_set")
+    )]
+    walls: HashSet<(usize, usize, Direction)>,
+    // No entity finishes growing before this time. Forgotten whenever a tile
+    // is changed.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    growth_due: Option<Duration>,
+}
+
+/// Deserialized fields of a `World`, checked before they are trusted.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct WorldState {
     width: usize,
     height: usize,
     tile_refs: Array2<Option<TileId>>,
     tiles: HashMap<TileId, Tile>,
+    next_tile_id: TileId,
+    walls: HashSet<(usize, usize, Direction)>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<WorldState> for World {
+    type Error = String;
+
+    fn try_from(state: WorldState) -> Result<Self, Self::Error> {
+        let (width, height) = (state.width, state.height);
+        if width == 0 || height == 0 {
+            return Err(format!("world size {width}x{height} is empty"));
+        }
+        if state.tile_refs.dim() != (width, height) {
+            return Err(format!(
+                "tile grid is {:?} but the world is {width}x{height}",
+                state.tile_refs.dim()
+            ));
+        }
+        for tile_id in &state.tile_refs {
+            match tile_id {
+                Some(tile_id) if state.tiles.contains_key(tile_id) => {}
+                Some(tile_id) => return Err(format!("tile {tile_id} does not exist")),
+                None => return Err("tile grid has a hole".to_string()),
+            }
+        }
+        if let Some(tile_id) = state.tiles.keys().find(|&&id| id >= state.next_tile_id) {
+            return Err(format!("tile {tile_id} is not below the next tile id"));
+        }
+        if let Some(wall) = state
+            .walls
+            .iter()
+            .find(|&&(x, y, _)| x >= width || y >= height)
+        {
+            return Err(format!("wall {wall:?} is out of bounds"));
+        }
+        Ok(Self {
+            width,
+            height,
+            tile_refs: Arc::new(state.tile_refs),
+            tiles: state.tiles,
+            next_tile_id: state.next_tile_id,
+            walls: state.walls,
+            growth_due: None,
+        })
+    }
 }
 
 impl World {
@@ -39,11 +121,82 @@ impl World {
         Ok(Self {
             width,
             height,
-            tile_refs,
+            tile_refs: Arc::new(tile_refs),
             tiles,
+            next_tile_id,
+            walls: HashSet::new(),
+            growth_due: None,
         })
     }
 
+    /// Grows or shrinks the world. Tiles inside both the old and the new
+    /// bounds keep their ids and contents, new tiles are empty grassland and
+    /// tiles that fall outside are dropped along with their walls.
+    pub fn resize(&mut self, new_width: usize, new_height: usize) -> Result<(), EngineError> {
+        self.growth_due = None;
+        if new_width == 0 || new_height == 0 {
+            return Err(EngineError::InvalidWorldSize {
+                width: new_width,
+                height: new_height,
+            });
+        }
+
+        let mut tile_refs = Array2::from_elem((new_width, new_height), None);
+        for x in 0..new_width {
+            for y in 0..new_height {
+                let tile_id = if x < self.width && y < self.height {
+                    self.tile_refs[(x, y)]
+                } else {
+                    let tile = TileBuilder::new(self.next_tile_id)
+                        .ground_type(GroundType::Grassland)
+                        .entity(None)
+                        .build()?;
+                    self.tiles.insert(self.next_tile_id, tile);
+                    self.next_tile_id += 1;
+                    Some(self.next_tile_id - 1)
+                };
+                tile_refs[(x, y)] = tile_id;
+            }
+        }
+        for x in 0..self.width {
+            for y in 0..self.height {
+                if x >= new_width || y >= new_height {
+                    if let Some(tile_id) = self.tile_refs[(x, y)] {
+                        self.tiles.remove(&tile_id);
+                    }
+                }
+            }
+        }
+
+        self.walls = self
+            .walls
+            .iter()
+            .filter_map(|&wall| self.resized_wall(wall, new_width, new_height))
+            .collect();
+        self.width = new_width;
+        self.height = new_height;
+        self.tile_refs = Arc::new(tile_refs);
+        Ok(())
+    }
+
+    /// Key of `wall` once the world is `new_width` by `new_height`. Walls
+    /// across the wrapping edges stay on the edges, other walls are dropped
+    /// unless both of their tiles remain.
+    fn resized_wall(
+        &self,
+        (x, y, direction): (usize, usize, Direction),
+        new_width: usize,
+        new_height: usize,
+    ) -> Option<(usize, usize, Direction)> {
+        let (x, y, far_x, far_y) = match direction {
+            Direction::East if x == self.width - 1 => (new_width - 1, y, 0, y),
+            Direction::East => (x, y, x + 1, y),
+            _ if y == self.height - 1 => (x, new_height - 1, x, 0),
+            _ => (x, y, x, y + 1),
+        };
+        (x.max(far_x) < new_width && y.max(far_y) < new_height).then_some((x, y, direction))
+    }
+
     pub fn width(&self) -> usize {
         self.width
     }
@@ -52,6 +205,10 @@ impl World {
         self.height
     }
 
+    pub fn tiles(&self) -> impl Iterator<Item = &Tile> {
+        self.tiles.values()
+    }
+
     pub fn get_tile(&self, x: usize, y: usize) -> Result<&Tile, EngineError> {
         if x >= self.width || y >= self.height {
             return Err(EngineError::DroneOutOfBounds { x, y });
@@ -65,6 +222,7 @@ impl World {
     }
 
     pub fn mut_tile(&mut self, x: usize, y: usize) -> Result<&mut Tile, EngineError> {
+        self.growth_due = None;
         if x >= self.width || y >= self.height {
             return Err(EngineError::DroneOutOfBounds { x, y });
         }
@@ -76,17 +234,117 @@ impl World {
             .ok_or(EngineError::DroneOutOfBounds { x, y })
     }
 
-    pub fn clear(&mut self) -> Result<(), EngineError> {
+    pub fn neighbor(&self, x: usize, y: usize, direction: Direction) -> (usize, usize) {
+        match direction {
+            Direction::North => (x, (y + self.height - 1) % self.height),
+            Direction::East => ((x + 1) % self.width, y),
+            Direction::South => (x, (y + 1) % self.height),
+            Direction::West => ((x + self.width - 1) % self.width, y),
+        }
+    }
+
+    /// Position `(dx, dy)` away from `(x, y)`, wrapping around the edges.
+    pub fn offset(&self, x: usize, y: usize, dx: isize, dy: isize) -> (usize, usize) {
+        (
+            (x as isize + dx).rem_euclid(self.width as isize) as usize,
+            (y as isize + dy).rem_euclid(self.height as isize) as usize,
+        )
+    }
+
+    pub fn bounded_neighbor(
+        &self,
+        x: usize,
+        y: usize,
+        direction: Direction,
+    ) -> Option<(usize, usize)> {
+        match direction {
+            Direction::North => y.checked_sub(1).map(|y| (x, y)),
+            Direction::East => (x + 1 < self.width).then_some((x + 1, y)),
+            Direction::South => (y + 1 < self.height).then_some((x, y + 1)),
+            Direction::West => x.checked_sub(1).map(|x| (x, y)),
+        }
+    }
+
+    fn wall_key(&self, x: usize, y: usize, direction: Direction) -> (usize, usize, Direction) {
+        match direction {
+            Direction::North => {
+                let (nx, ny) = self.neighbor(x, y, direction);
+                (nx, ny, Direction::South)
+            }
+            Direction::West => {
+                let (nx, ny) = self.neighbor(x, y, direction);
+                (nx, ny, Direction::East)
+            }
+            Direction::East | Direction::South => (x, y, direction),
+        }
+    }
+
+    pub fn has_wall(&self, x: usize, y: usize, direction: Direction) -> bool {
+        self.walls.contains(&self.wall_key(x, y, direction))
+    }
+
+    pub fn set_wall(&mut self, x: usize, y: usize, direction: Direction, wall: bool) {
+        let key = self.wall_key(x, y, direction);
+        if wall {
+            self.walls.insert(key);
+        } else {
+            self.walls.remove(&key);
+        }
+    }
+
+    /// Time before which no entity finishes growing, if still known.
+    pub(crate) fn growth_due(&self) -> Option<Duration> {
+        self.growth_due
+    }
+
+    pub(crate) fn set_growth_due(&mut self, due: Duration) {
+        self.growth_due = Some(due);
+    }
+
+    pub fn clear_walls(&mut self) {
+        self.walls.clear();
+    }
+
+    pub fn swap_entities(
+        &mut self,
+        (ax, ay): (usize, usize),
+        (bx, by): (usize, usize),
+    ) -> Result<(), EngineError> {
+        let a = self.get_tile(ax, ay)?;
+        let b = self.get_tile(bx, by)?;
+        for (entity, ground_type) in [(a.entity(), b.ground_type()), (b.entity(), a.ground_type())]
+        {
+            if let Some(entity) = entity {
+                if !ground_type.can_have_entity(entity) {
+                    return Err(EngineError::EntityNotAllowedOnGroundType(
+                        entity.entity_type().clone(),
+                        ground_type.clone(),
+                    ));
+                }
+            }
+        }
+
+        let a_entity = self.mut_tile(ax, ay)?.take_entity();
+        let b_entity = self.mut_tile(bx, by)?.take_entity();
+        self.mut_tile(ax, ay)?.set_entity(b_entity)?;
+        self.mut_tile(bx, by)?.set_entity(a_entity)?;
+        Ok(())
+    }
+
+    pub fn clear(&mut self, now: Duration) -> Result<(), EngineError> {
+        self.growth_due = None;
         for x in 0..self.width {
             for y in 0..self.height {
                 if let Some(tile_id) = self.tile_refs[(x, y)] {
                     if let Some(tile) = self.tiles.get_mut(&tile_id) {
                         tile.set_ground_type(GroundType::Grassland);
-                        tile.set_entity(Some(Entity::from(EntityType::Grass)))?;
+                        tile.set_entity(Some(Entity::new(EntityType::Grass, now)))?;
+                        tile.set_water(0.0, now);
                     }
                 }
             }
         }
+        self.clear_walls();
         Ok(())
     }
 }
@@ -109,6 +367,157 @@ mod tests {
         assert_eq!(tile.ground_type(), &GroundType::Grassland);
     }
 
+    #[test]
+    fn neighbor_wraps_around_edges() {
+        let world = World::new(4, 3).unwrap();
+        assert_eq!(world.neighbor(0, 0, Direction::North), (0, 2));
+        assert_eq!(world.neighbor(0, 0, Direction::West), (3, 0));
+        assert_eq!(world.neighbor(3, 2, Direction::East), (0, 2));
+        assert_eq!(world.neighbor(3, 2, Direction::South), (3, 0));
+    }
+
+    #[test]
+    fn offset_wraps_around_edges() {
+        let world = World::new(4, 3).unwrap();
+        assert_eq!(world.offset(1, 1, 2, -1), (3, 0));
+        assert_eq!(world.offset(0, 0, -2, 4), (2, 1));
+    }
+
+    #[test]
+    fn resizing_keeps_existing_tiles() {
+        let mut world = World::new(2, 2).unwrap();
+        world
+            .mut_tile(1, 1)
+            .unwrap()
+            .set_entity(Some(Entity::from(EntityType::Tree)))
+            .unwrap();
+
+        world.resize(4, 3).unwrap();
+        assert_eq!((world.width(), world.height()), (4, 3));
+        assert_eq!(world.tiles().count(), 12);
+        assert!(world.get_tile(1, 1).unwrap().entity().is_some());
+        assert!(world.get_tile(3, 2).unwrap().entity().is_none());
+        assert_eq!(world.tile_refs[(1, 1)], Some(3));
+        assert_eq!(world.tile_refs[(3, 2)], Some(11));
+    }
+
+    #[test]
+    fn shrinking_drops_tiles_outside_new_bounds() {
+        let mut world = World::new(3, 3).unwrap();
+        world.set_wall(2, 0, Direction::South, true);
+        world.set_wall(0, 0, Direction::East, true);
+
+        world.resize(2, 2).unwrap();
+        assert_eq!(world.tiles().count(), 4);
+        assert!(world.get_tile(2, 0).is_err());
+        assert!(world.has_wall(0, 0, Direction::East));
+        assert!(!world.walls.contains(&(2, 0, Direction::South)));
+
+        world.resize(3, 3).unwrap();
+        assert_eq!(world.tiles().count(), 9);
+        assert!(world
+            .tile_refs
+            .iter()
+            .flatten()
+            .all(|tile_id| world.tiles.contains_key(tile_id)));
+    }
+
+    #[test]
+    fn clones_share_tile_layout_until_resized() {
+        let mut world = World::new(3, 3).unwrap();
+        let snapshot = world.clone();
+        world.mut_tile(1, 1).unwrap().set_water(1.0, Duration::ZERO);
+        assert!(Arc::ptr_eq(&world.tile_refs, &snapshot.tile_refs));
+
+        world.resize(4, 4).unwrap();
+        assert!(!Arc::ptr_eq(&world.tile_refs, &snapshot.tile_refs));
+        assert_eq!(snapshot.tile_refs.dim(), (3, 3));
+    }
+
+    #[test]
+    fn cannot_resize_to_empty_world() {
+        let mut world = World::new(3, 3).unwrap();
+        assert!(matches!(
+            world.resize(0, 3).unwrap_err(),
+            EngineError::InvalidWorldSize {
+                width: 0,
+                height: 3
+            }
+        ));
+        assert_eq!(world.width(), 3);
+    }
+
+    #[test]
+    fn walls_block_both_sides_of_an_edge() {
+        let mut world = World::new(3, 3).unwrap();
+        world.set_wall(1, 1, Direction::North, true);
+        assert!(world.has_wall(1, 1, Direction::North));
+        assert!(world.has_wall(1, 0, Direction::South));
+        assert!(!world.has_wall(1, 1, Direction::South));
+
+        world.set_wall(1, 0, Direction::South, false);
+        assert!(!world.has_wall(1, 1, Direction::North));
+    }
+
+    #[test]
+    fn walls_wrap_around_edges() {
+        let mut world = World::new(3, 3).unwrap();
+        world.set_wall(0, 0, Direction::West, true);
+        assert!(world.has_wall(2, 0, Direction::East));
+    }
+
+    #[test]
+    fn bounded_neighbor_stops_at_edges() {
+        let world = World::new(4, 3).unwrap();
+        assert_eq!(world.bounded_neighbor(0, 0, Direction::North), None);
+        assert_eq!(world.bounded_neighbor(0, 0, Direction::West), None);
+        assert_eq!(world.bounded_neighbor(0, 0, Direction::East), Some((1, 0)));
+        assert_eq!(world.bounded_neighbor(3, 2, Direction::South), None);
+    }
+
+    #[test]
+    fn can_swap_entities_between_tiles() {
+        let mut world = World::new(3, 3).unwrap();
+        world
+            .mut_tile(0, 0)
+            .unwrap()
+            .set_entity(Some(Entity::from(EntityType::Tree)))
+            .unwrap();
+
+        world.swap_entities((0, 0), (1, 0)).unwrap();
+        assert!(world.get_tile(0, 0).unwrap().entity().is_none());
+        assert_eq!(
+            world
+                .get_tile(1, 0)
+                .unwrap()
+                .entity()
+                .unwrap()
+                .entity_type(),
+            &EntityType::Tree
+        );
+    }
+
+    #[test]
+    fn cannot_swap_entity_onto_incompatible_ground() {
+        let mut world = World::new(3, 3).unwrap();
+        world
+            .mut_tile(0, 0)
+            .unwrap()
+            .set_entity(Some(Entity::from(EntityType::Tree)))
+            .unwrap();
+        world
+            .mut_tile(1, 0)
+            .unwrap()
+            .set_ground_type(GroundType::Soil);
+
+        let result = world.swap_entities((0, 0), (1, 0));
+        assert!(matches!(
+            result.unwrap_err(),
+            EngineError::EntityNotAllowedOnGroundType(EntityType::Tree, GroundType::Soil)
+        ));
+        assert!(world.get_tile(0, 0).unwrap().entity().is_some());
+    }
+
     #[test]
     fn clear_resets_all_tiles_to_grassland_with_grass() {
         let mut world = World::new(5, 5).unwrap();
@@ -139,7 +548,7 @@ mod tests {
             .set_entity(Some(Entity::from(EntityType::Tree)))
             .unwrap();
 
-        world.clear().unwrap();
+        world.clear(Duration::ZERO).unwrap();
 
         for x in 0..world.width() {
             for y in 0..world.height() {
@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use crate::{
+    consts,
+    display::parse_tile_symbol,
+    error::EngineError,
+    world::{entities::Entity, tiles::GroundType, World},
+};
+
+impl World {
+    /// Parses the format of `Game::get_world_grid_string`: one line per row
+    /// from the top (`y = height - 1`) down, one symbol per tile.
+    ///
+    /// The grid may be followed by annotation lines of the form
+    /// `@x,y token...`, where each token is a tile symbol (e.g. for the tile
+    /// hidden under a drone), `ground=soil` or `ground=grassland` (e.g. for
+    /// the ground under an apple), `grown`, `progress=SECONDS` or `size=N`.
+    pub fn from_ascii(map: &str) -> Result<World, EngineError> {
+        Self::parse_ascii(map).map(|(world, _)| world)
+    }
+
+    /// Like `from_ascii`, also returning the drone positions in reading
+    /// order.
+    pub(crate) fn parse_ascii(map: &str) -> Result<(World, Vec<(usize, usize)>), EngineError> {
+        let invalid = |line: usize, reason: String| EngineError::InvalidAsciiMap {
+            line: line + 1,
+            reason,
+        };
+        let lines: Vec<_> = map
+            .lines()
+            .map(str::trim)
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .collect();
+        let (rows, annotations): (Vec<_>, Vec<_>) = lines
+            .into_iter()
+            .partition(|(_, line)| !line.starts_with('@'));
+
+        let height = rows.len();
+        let width = rows.first().map_or(0, |(_, row)| row.chars().count());
+        if width == 0 {
+            return Err(invalid(0, "Map has no tiles.".to_string()));
+        }
+
+        let mut world = World::new(width, height)?;
+        let mut drones = Vec::new();
+        for (row, &(line, symbols)) in rows.iter().enumerate() {
+            if symbols.chars().count() != width {
+                return Err(invalid(line, format!("Expected {width} tiles.")));
+            }
+            let y = height - 1 - row;
+            for (x, symbol) in symbols.chars().enumerate() {
+                let symbol = symbol.to_string();
+                if symbol == consts::DRONE_SYMBOL {
+                    drones.push((x, y));
+                }
+                set_tile(&mut world, x, y, &symbol)
+                    .ok_or_else(|| invalid(line, format!("Unknown symbol `{symbol}`.")))??;
+            }
+        }
+
+        for (line, annotation) in annotations {
+            let mut tokens = annotation[1..].split_whitespace();
+            let position = tokens
+                .next()
+                .and_then(|position| position.split_once(','))
+                .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)));
+            let Some((x, y)) = position else {
+                return Err(invalid(line, "Expected `@x,y`.".to_string()));
+            };
+            if world.get_tile(x, y).is_err() {
+                return Err(invalid(
+                    line,
+                    format!("Tile ({x}, {y}) is outside the map."),
+                ));
+            }
+            for token in tokens {
+                annotate(&mut world, x, y, token)
+                    .ok_or_else(|| invalid(line, format!("Invalid annotation `{token}`.")))??;
+            }
+        }
+        Ok((world, drones))
+    }
+}
+
+fn set_tile(
+    world: &mut World,
+    x: usize,
+    y: usize,
+    symbol: &str,
+) -> Option<Result<(), EngineError>> {
+    let (ground_type, entity_type) = parse_tile_symbol(symbol)?;
+    let tile = world.mut_tile(x, y).ok()?;
+    tile.set_ground_type(ground_type);
+    let entity = entity_type.map(|entity_type| Entity::new(entity_type, Duration::ZERO));
+    Some(tile.set_entity(entity).map(|_| ()))
+}
+
+fn annotate(world: &mut World, x: usize, y: usize, token: &str) -> Option<Result<(), EngineError>> {
+    if token != consts::DRONE_SYMBOL && parse_tile_symbol(token).is_some() {
+        return set_tile(world, x, y, token);
+    }
+    let tile = world.mut_tile(x, y).ok()?;
+    if let Some(("ground", ground)) = token.split_once('=') {
+        let ground_type = match ground {
+            "soil" => GroundType::Soil,
+            "grassland" => GroundType::Grassland,
+            _ => return None,
+        };
+        if tile
+            .entity()
+            .is_some_and(|entity| !ground_type.can_have_entity(entity))
+        {
+            return None;
+        }
+        tile.set_ground_type(ground_type);
+        return Some(Ok(()));
+    }
+    let entity = tile.mut_entity()?;
+    match token.split_once('=') {
+        None if token == "grown" => {
+            let remaining = entity.growth_time().saturating_sub(entity.progress());
+            entity.grow(remaining, Duration::ZERO);
+        }
+        Some(("progress", seconds)) => {
+            let seconds = seconds.parse::<f64>().ok().filter(|s| *s >= 0.0)?;
+            entity.grow(Duration::from_secs_f64(seconds), Duration::ZERO);
+        }
+        Some(("size", size)) => {
+            entity.set_measure(Some(size.parse().ok()?));
+        }
+        _ => return None,
+    }
+    Some(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::{Game, GameOptions, Measurement},
+        world::{entities::EntityType, tiles::GroundType},
+    };
+
+    #[test]
+    fn can_parse_grid() {
+        let world = World::from_ascii(
+            "GBT
+             SCP",
+        )
+        .unwrap();
+
+        assert_eq!((world.width(), world.height()), (3, 2));
+        let top_left = world.get_tile(0, 1).unwrap();
+        assert_eq!(top_left.entity().unwrap().entity_type(), &EntityType::Grass);
+        let bottom_right = world.get_tile(2, 0).unwrap();
+        assert_eq!(bottom_right.ground_type(), &GroundType::Soil);
+        assert_eq!(
+            bottom_right.entity().unwrap().entity_type(),
+            &EntityType::Pumpkin
+        );
+    }
+
+    #[test]
+    fn annotations_set_growth_and_hidden_tiles() {
+        let world = World::from_ascii(
+            "DT.
+             @0,0 C progress=1.5
+             @1,0 grown
+             ",
+        )
+        .unwrap();
+
+        let under_drone = world.get_tile(0, 0).unwrap();
+        assert_eq!(under_drone.ground_type(), &GroundType::Soil);
+        assert_eq!(
+            under_drone.entity().unwrap().progress(),
+            Duration::from_secs_f64(1.5)
+        );
+        assert!(world.get_tile(1, 0).unwrap().is_grown(Duration::ZERO));
+    }
+
+    #[test]
+    fn cannot_parse_invalid_maps() {
+        for (map, line) in [
+            ("", 1),
+            ("GG\nG", 2),
+            ("GZ", 1),
+            ("G\n@3,0 grown", 2),
+            ("G\n@0,0 tall", 2),
+            ("G\n@0,0 D", 2),
+            ("C\n@0,0 ground=grassland", 2),
+            (".\n@0,0 ground=sand", 2),
+        ] {
+            let error = World::from_ascii(map).map(|_| ()).unwrap_err();
+            assert!(
+                matches!(error, EngineError::InvalidAsciiMap { line: l, .. } if l == line),
+                "{map:?} gave {error:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn game_round_trips_through_grid_string() {
+        let grid = "G.TD\nSCpB\nXF$A";
+        let game = Game::from_grid_str(GameOptions::default(), grid).unwrap();
+
+        assert_eq!(game.drone_position(), (3, 2));
+        assert_eq!(game.get_world_grid_string(), grid);
+    }
+
+    #[test]
+    fn apple_on_soil_round_trips() {
+        let grid = "DSA\n@2,0 ground=soil";
+        let game = Game::from_grid_str(GameOptions::default(), grid).unwrap();
+
+        let apple = game.world().get_tile(2, 0).unwrap();
+        assert_eq!(apple.ground_type(), &GroundType::Soil);
+        assert_eq!(apple.entity().unwrap().entity_type(), &EntityType::Apple);
+        assert_eq!(game.get_world_grid_string(), grid);
+    }
+
+    #[test]
+    fn every_drone_symbol_places_a_drone() {
+        let mut game = Game::from_grid_str(
+            GameOptions::default(),
+            "D.
+             .D
+             @0,1 X size=4",
+        )
+        .unwrap();
+
+        assert_eq!(game.drone_positions(), vec![(0, 1), (1, 0)]);
+        assert_eq!(game.elapsed_ticks(), 0);
+        assert_eq!(game.measure().unwrap(), Some(Measurement::Size(4)));
+        assert_eq!(game.get_entity_type().unwrap(), Some(&EntityType::Cactus));
+    }
+}
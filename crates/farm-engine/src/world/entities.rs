@@ -1,41 +1,186 @@
-use chrono::{Duration, NaiveTime};
+use std::time::Duration;
+
+use rand::Rng;
 
 use crate::consts;
+use crate::game::inventory::Item;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EntityType {
     Grass,
     Bush,
     Carrot,
     Pumpkin,
     Tree,
+    Sunflower,
+    Cactus,
+    DeadPumpkin,
+    Hedge,
+    Treasure,
+    Apple,
 }
 
 impl EntityType {
     pub fn growth_time(&self) -> Duration {
         match self {
-            EntityType::Grass => {
-                Duration::milliseconds((consts::GRASS_GROWTH_TIME * 1000.0) as i64)
-            }
-            EntityType::Bush => Duration::milliseconds((consts::BUSH_GROWTH_TIME * 1000.0) as i64),
-            EntityType::Carrot => {
-                Duration::milliseconds((consts::CARROT_GROWTH_TIME * 1000.0) as i64)
-            }
-            EntityType::Pumpkin => {
-                Duration::milliseconds((consts::PUMPKIN_GROWTH_TIME * 1000.0) as i64)
-            }
-            EntityType::Tree => Duration::milliseconds((consts::TREE_GROWTH_TIME * 1000.0) as i64),
+            EntityType::Grass => Duration::from_secs_f64(consts::GRASS_GROWTH_TIME),
+            EntityType::Bush => Duration::from_secs_f64(consts::BUSH_GROWTH_TIME),
+            EntityType::Carrot => Duration::from_secs_f64(consts::CARROT_GROWTH_TIME),
+            EntityType::Pumpkin => Duration::from_secs_f64(consts::PUMPKIN_GROWTH_TIME),
+            EntityType::Tree => Duration::from_secs_f64(consts::TREE_GROWTH_TIME),
+            EntityType::Sunflower => Duration::from_secs_f64(consts::SUNFLOWER_GROWTH_TIME),
+            EntityType::Cactus => Duration::from_secs_f64(consts::CACTUS_GROWTH_TIME),
+            EntityType::DeadPumpkin
+            | EntityType::Hedge
+            | EntityType::Treasure
+            | EntityType::Apple => Duration::ZERO,
+        }
+    }
+
+    pub fn planting_cost(&self) -> &'static [(Item, u64)] {
+        match self {
+            EntityType::Carrot => &[
+                (Item::Hay, consts::CARROT_HAY_COST),
+                (Item::Wood, consts::CARROT_WOOD_COST),
+            ],
+            EntityType::Pumpkin => &[(Item::Carrot, consts::PUMPKIN_CARROT_COST)],
+            EntityType::Sunflower => &[(Item::Carrot, consts::SUNFLOWER_CARROT_COST)],
+            EntityType::Grass
+            | EntityType::Bush
+            | EntityType::Tree
+            | EntityType::Cactus
+            | EntityType::DeadPumpkin
+            | EntityType::Hedge
+            | EntityType::Treasure
+            | EntityType::Apple => &[],
+        }
+    }
+
+    /// Entity types that can be planted, as opposed to ones that only
+    /// appear through other mechanics.
+    pub fn is_plantable(&self) -> bool {
+        matches!(
+            self,
+            EntityType::Grass
+                | EntityType::Bush
+                | EntityType::Carrot
+                | EntityType::Pumpkin
+                | EntityType::Tree
+                | EntityType::Sunflower
+                | EntityType::Cactus
+        )
+    }
+
+    /// Entity types that can ask for a companion, and be asked for as one.
+    pub fn is_polyculture(&self) -> bool {
+        matches!(
+            self,
+            EntityType::Grass | EntityType::Bush | EntityType::Tree | EntityType::Carrot
+        )
+    }
+
+    pub fn harvest_yield(&self) -> Option<(Item, u64)> {
+        match self {
+            EntityType::Grass => Some((Item::Hay, consts::GRASS_HAY_YIELD)),
+            EntityType::Bush => Some((Item::Wood, consts::BUSH_WOOD_YIELD)),
+            EntityType::Carrot => Some((Item::Carrot, consts::CARROT_YIELD)),
+            EntityType::Pumpkin => Some((Item::Pumpkin, consts::PUMPKIN_YIELD)),
+            EntityType::Tree => Some((Item::Wood, consts::TREE_WOOD_YIELD)),
+            EntityType::Sunflower => Some((Item::Power, consts::SUNFLOWER_POWER_YIELD)),
+            EntityType::Cactus => Some((Item::Cactus, consts::CACTUS_YIELD)),
+            EntityType::Treasure => Some((Item::Gold, consts::TREASURE_GOLD_PER_TILE)),
+            EntityType::DeadPumpkin | EntityType::Hedge | EntityType::Apple => None,
+        }
+    }
+}
+
+/// A neighbor an entity wants planted next to it. Harvesting the entity
+/// while the companion is in place multiplies its yield.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Companion {
+    entity_type: EntityType,
+    position: (usize, usize),
+}
+
+impl Companion {
+    pub fn new(entity_type: EntityType, position: (usize, usize)) -> Self {
+        Self {
+            entity_type,
+            position,
         }
     }
+    pub fn entity_type(&self) -> &EntityType {
+        &self.entity_type
+    }
+    pub fn position(&self) -> (usize, usize) {
+        self.position
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Entity {
-    growth_time: NaiveTime,
+    planted_at: Duration,
+    base_growth_time: Duration,
+    growth_time: Duration,
+    progress: Duration,
+    updated_at: Duration,
+    measure: Option<u32>,
+    withers: bool,
+    companion: Option<Companion>,
     entity_type: EntityType,
 }
 
 impl Entity {
+    pub fn new(entity_type: EntityType, planted_at: Duration) -> Self {
+        let growth_time = entity_type.growth_time();
+        Self::with_growth_time(entity_type, planted_at, growth_time)
+    }
+    pub fn with_growth_time(
+        entity_type: EntityType,
+        planted_at: Duration,
+        growth_time: Duration,
+    ) -> Self {
+        Entity {
+            planted_at,
+            base_growth_time: growth_time,
+            growth_time,
+            progress: Duration::ZERO,
+            updated_at: planted_at,
+            measure: None,
+            withers: false,
+            companion: None,
+            entity_type,
+        }
+    }
+    /// A newly planted entity with its growth time jittered by up to
+    /// `growth_variance`, its measure rolled, and pumpkins set to wither
+    /// with `pumpkin_death_chance`.
+    pub(crate) fn planted(
+        entity_type: EntityType,
+        now: Duration,
+        rng: &mut impl Rng,
+        growth_variance: f64,
+        pumpkin_death_chance: f64,
+    ) -> Self {
+        let factor = rng.random_range(1.0 - growth_variance..=1.0 + growth_variance);
+        let growth_time = entity_type.growth_time().mul_f64(factor);
+        let measure = match entity_type {
+            EntityType::Sunflower => {
+                Some(rng.random_range(consts::SUNFLOWER_MIN_PETALS..=consts::SUNFLOWER_MAX_PETALS))
+            }
+            EntityType::Cactus => {
+                Some(rng.random_range(consts::CACTUS_MIN_SIZE..=consts::CACTUS_MAX_SIZE))
+            }
+            _ => None,
+        };
+        let withers = entity_type == EntityType::Pumpkin && rng.random_bool(pumpkin_death_chance);
+        let mut entity = Self::with_growth_time(entity_type, now, growth_time);
+        entity.set_measure(measure).set_withers(withers);
+        entity
+    }
     pub fn entity_type(&self) -> &EntityType {
         &self.entity_type
     }
@@ -43,27 +188,119 @@ impl Entity {
         self.entity_type = entity_type;
         self
     }
-    pub fn growth_time(&self) -> NaiveTime {
+    pub fn planted_at(&self) -> Duration {
+        self.planted_at
+    }
+    pub fn growth_time(&self) -> Duration {
         self.growth_time
     }
-    pub fn is_grown(&self) -> bool {
-        chrono::Local::now().time() >= self.growth_time
+    /// Scales the growth time the entity was planted with, e.g. to slow down
+    /// trees crowded by other trees. Progress made so far is kept.
+    pub fn set_growth_multiplier(&mut self, multiplier: f64) -> &mut Self {
+        self.growth_time = self.base_growth_time.mul_f64(multiplier);
+        self
+    }
+    pub fn progress(&self) -> Duration {
+        self.progress
+    }
+    pub fn updated_at(&self) -> Duration {
+        self.updated_at
+    }
+    /// Records `amount` of growth accumulated up to `now`.
+    pub fn grow(&mut self, amount: Duration, now: Duration) -> &mut Self {
+        self.progress += amount;
+        self.updated_at = now;
+        self
+    }
+    /// Whether the growth recorded so far is complete. Growth since the last
+    /// `Tile::settle_growth` is not included, see `Tile::is_grown`.
+    pub(crate) fn is_grown(&self) -> bool {
+        self.progress >= self.growth_time
+    }
+    /// Type-specific size of the entity, e.g. the petal count of a sunflower
+    /// or the size of a cactus.
+    pub fn measure(&self) -> Option<u32> {
+        self.measure
+    }
+    pub fn set_measure(&mut self, measure: Option<u32>) -> &mut Self {
+        self.measure = measure;
+        self
+    }
+    /// Whether the entity dies once it is fully grown, as pumpkins sometimes do.
+    pub fn withers(&self) -> bool {
+        self.withers
     }
-    #[cfg(test)]
-    pub fn is_grown_at(&self, time: NaiveTime) -> bool {
-        time >= self.growth_time
+    pub fn set_withers(&mut self, withers: bool) -> &mut Self {
+        self.withers = withers;
+        self
+    }
+    pub fn companion(&self) -> Option<&Companion> {
+        self.companion.as_ref()
+    }
+    pub fn set_companion(&mut self, companion: Option<Companion>) -> &mut Self {
+        self.companion = companion;
+        self
     }
 }
 
 impl From<EntityType> for Entity {
     fn from(entity_type: EntityType) -> Self {
-        let now = chrono::Local::now().time();
-        let growth_duration = entity_type.growth_time();
-        let growth_time = now + growth_duration;
+        Entity::new(entity_type, Duration::ZERO)
+    }
+}
 
-        Entity {
-            growth_time,
-            entity_type,
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entity_grows_after_accumulating_its_growth_time() {
+        let planted_at = Duration::from_secs(3);
+        let mut entity = Entity::new(EntityType::Tree, planted_at);
+
+        assert_eq!(entity.growth_time(), EntityType::Tree.growth_time());
+        assert!(!entity.is_grown());
+
+        entity.grow(Duration::from_secs(2), planted_at + Duration::from_secs(2));
+        assert!(!entity.is_grown());
+        assert_eq!(entity.updated_at(), Duration::from_secs(5));
+
+        entity.grow(Duration::from_secs(3), planted_at + Duration::from_secs(3));
+        assert!(entity.is_grown());
+    }
+
+    #[test]
+    fn growth_multiplier_scales_planted_growth_time() {
+        let mut entity =
+            Entity::with_growth_time(EntityType::Tree, Duration::ZERO, Duration::from_secs(4));
+
+        entity.set_growth_multiplier(2.0);
+        assert_eq!(entity.growth_time(), Duration::from_secs(8));
+        entity.set_growth_multiplier(1.0);
+        assert_eq!(entity.growth_time(), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn harvest_yields_match_entity_type() {
+        assert_eq!(EntityType::Grass.harvest_yield(), Some((Item::Hay, 1)));
+        assert_eq!(EntityType::Bush.harvest_yield(), Some((Item::Wood, 1)));
+        assert_eq!(EntityType::Tree.harvest_yield(), Some((Item::Wood, 5)));
+        assert_eq!(EntityType::Carrot.harvest_yield(), Some((Item::Carrot, 1)));
+        assert_eq!(
+            EntityType::Pumpkin.harvest_yield(),
+            Some((Item::Pumpkin, 1))
+        );
+        assert_eq!(EntityType::DeadPumpkin.harvest_yield(), None);
+    }
+
+    #[test]
+    fn only_crops_cost_resources_to_plant() {
+        assert!(EntityType::Grass.planting_cost().is_empty());
+        assert!(EntityType::Tree.planting_cost().is_empty());
+        assert_eq!(
+            EntityType::Carrot.planting_cost(),
+            &[(Item::Hay, 1), (Item::Wood, 1)]
+        );
+        assert_eq!(EntityType::Pumpkin.planting_cost(), &[(Item::Carrot, 1)]);
     }
 }
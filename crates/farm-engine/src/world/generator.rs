@@ -0,0 +1,338 @@
+use std::time::Duration;
+
+use ndarray::Array2;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    consts,
+    error::EngineError,
+    world::{
+        entities::{Entity, EntityType},
+        tiles::GroundType,
+        World,
+    },
+};
+
+/// Builds seeded starting layouts. Soil and entities come in patches whose
+/// size follows `patch_size`, so neighboring tiles tend to look alike.
+#[derive(Debug, Clone)]
+pub struct WorldGenerator {
+    seed: u64,
+    width: usize,
+    height: usize,
+    soil_ratio: f64,
+    planted_ratio: f64,
+    entity_weights: Vec<(EntityType, f64)>,
+    patch_size: usize,
+    growth_variance: f64,
+    pumpkin_death_chance: f64,
+}
+
+impl WorldGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            width: consts::DEFAULT_WORLD_SIZE,
+            height: consts::DEFAULT_WORLD_SIZE,
+            soil_ratio: consts::DEFAULT_SOIL_RATIO,
+            planted_ratio: consts::DEFAULT_PLANTED_RATIO,
+            entity_weights: vec![
+                (EntityType::Grass, 4.0),
+                (EntityType::Bush, 2.0),
+                (EntityType::Tree, 1.0),
+                (EntityType::Carrot, 2.0),
+                (EntityType::Pumpkin, 1.0),
+            ],
+            patch_size: consts::DEFAULT_PATCH_SIZE,
+            growth_variance: consts::DEFAULT_GROWTH_VARIANCE,
+            pumpkin_death_chance: consts::DEFAULT_PUMPKIN_DEATH_CHANCE,
+        }
+    }
+
+    pub fn size(mut self, width: usize, height: usize) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Fraction of tiles that are tilled.
+    pub fn soil_ratio(mut self, soil_ratio: f64) -> Self {
+        self.soil_ratio = soil_ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Chance for each tile to start with an entity on it.
+    pub fn planted_ratio(mut self, planted_ratio: f64) -> Self {
+        self.planted_ratio = planted_ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Relative weights of pre-planted entity types. Each tile only picks
+    /// among the types its ground allows. Fails for types that cannot be
+    /// planted.
+    pub fn entity_weights(
+        mut self,
+        entity_weights: Vec<(EntityType, f64)>,
+    ) -> Result<Self, EngineError> {
+        if let Some((entity_type, _)) = entity_weights
+            .iter()
+            .find(|(entity_type, _)| !entity_type.is_plantable())
+        {
+            return Err(EngineError::EntityNotPlantable(entity_type.clone()));
+        }
+        self.entity_weights = entity_weights;
+        Ok(self)
+    }
+
+    /// Typical width of soil and entity patches, `1` for no clustering.
+    pub fn patch_size(mut self, patch_size: usize) -> Self {
+        self.patch_size = patch_size.max(1);
+        self
+    }
+
+    /// Jitter of the growth times of pre-planted entities, as with
+    /// `GameOptions::growth_variance`.
+    pub fn growth_variance(mut self, growth_variance: f64) -> Self {
+        self.growth_variance = growth_variance.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Chance for each pre-planted pumpkin to wither, as with
+    /// `GameOptions::pumpkin_death_chance`.
+    pub fn pumpkin_death_chance(mut self, pumpkin_death_chance: f64) -> Self {
+        self.pumpkin_death_chance = pumpkin_death_chance.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn generate(&self) -> Result<World, EngineError> {
+        let mut world = World::new(self.width, self.height)?;
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let ground_noise = self.noise(&mut rng);
+        let entity_noise = self.noise(&mut rng);
+
+        // Tilling the lowest-noise tiles hits the soil ratio exactly while
+        // keeping the soil in patches.
+        let mut positions: Vec<_> = ground_noise.indexed_iter().collect();
+        positions.sort_by(|a, b| a.1.total_cmp(b.1));
+        let soil_tiles = (self.soil_ratio * positions.len() as f64).round() as usize;
+        for &((x, y), _) in positions.iter().take(soil_tiles) {
+            world.mut_tile(x, y)?.set_ground_type(GroundType::Soil);
+        }
+
+        for x in 0..self.width {
+            for y in 0..self.height {
+                if !rng.random_bool(self.planted_ratio) {
+                    continue;
+                }
+                let tile = world.mut_tile(x, y)?;
+                let allowed: Vec<_> = self
+                    .entity_weights
+                    .iter()
+                    .filter(|(entity_type, weight)| {
+                        *weight > 0.0
+                            && tile
+                                .ground_type()
+                                .can_have_entity(&Entity::from(entity_type.clone()))
+                    })
+                    .collect();
+                let total: f64 = allowed.iter().map(|(_, weight)| weight).sum();
+                let mut pick = entity_noise[(x, y)] * total;
+                for (entity_type, weight) in allowed {
+                    if pick < *weight {
+                        let entity = Entity::planted(
+                            entity_type.clone(),
+                            Duration::ZERO,
+                            &mut rng,
+                            self.growth_variance,
+                            self.pumpkin_death_chance,
+                        );
+                        tile.set_entity(Some(entity))?;
+                        break;
+                    }
+                    pick -= weight;
+                }
+            }
+        }
+        Ok(world)
+    }
+
+    /// Smooth value noise in `0.0..1.0`, interpolated between random values
+    /// on a lattice spaced `patch_size` tiles apart.
+    fn noise(&self, rng: &mut ChaCha8Rng) -> Array2<f64> {
+        let lattice_width = self.width / self.patch_size + 2;
+        let lattice_height = self.height / self.patch_size + 2;
+        let lattice =
+            Array2::from_shape_fn((lattice_width, lattice_height), |_| rng.random::<f64>());
+
+        let smooth = |t: f64| t * t * (3.0 - 2.0 * t);
+        Array2::from_shape_fn((self.width, self.height), |(x, y)| {
+            let (cx, cy) = (x / self.patch_size, y / self.patch_size);
+            let tx = smooth((x % self.patch_size) as f64 / self.patch_size as f64);
+            let ty = smooth((y % self.patch_size) as f64 / self.patch_size as f64);
+            let top = lattice[(cx, cy)] * (1.0 - tx) + lattice[(cx + 1, cy)] * tx;
+            let bottom = lattice[(cx, cy + 1)] * (1.0 - tx) + lattice[(cx + 1, cy + 1)] * tx;
+            top * (1.0 - ty) + bottom * ty
+        })
+    }
+}
+
+impl Default for WorldGenerator {
+    fn default() -> Self {
+        Self::new(consts::DEFAULT_SEED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(world: &World) -> Vec<(GroundType, Option<EntityType>)> {
+        let mut layout = Vec::new();
+        for x in 0..world.width() {
+            for y in 0..world.height() {
+                let tile = world.get_tile(x, y).unwrap();
+                layout.push((
+                    tile.ground_type().clone(),
+                    tile.entity().map(|entity| entity.entity_type().clone()),
+                ));
+            }
+        }
+        layout
+    }
+
+    fn matching_neighbors(world: &World) -> usize {
+        let mut matching = 0;
+        for x in 0..world.width() - 1 {
+            for y in 0..world.height() {
+                let here = world.get_tile(x, y).unwrap().ground_type();
+                let east = world.get_tile(x + 1, y).unwrap().ground_type();
+                matching += usize::from(here == east);
+            }
+        }
+        matching
+    }
+
+    #[test]
+    fn same_seed_generates_same_world() {
+        let generator = WorldGenerator::new(3).size(12, 8);
+        assert_eq!(
+            layout(&generator.generate().unwrap()),
+            layout(&generator.generate().unwrap())
+        );
+        assert_ne!(
+            layout(&generator.generate().unwrap()),
+            layout(&WorldGenerator::new(4).size(12, 8).generate().unwrap())
+        );
+    }
+
+    #[test]
+    fn soil_ratio_is_respected() {
+        let world = WorldGenerator::new(1)
+            .size(10, 10)
+            .soil_ratio(0.25)
+            .generate()
+            .unwrap();
+        let soil = world
+            .tiles()
+            .filter(|tile| tile.ground_type() == &GroundType::Soil)
+            .count();
+        assert_eq!(soil, 25);
+    }
+
+    #[test]
+    fn entities_only_grow_on_allowed_ground() {
+        let world = WorldGenerator::new(5)
+            .size(16, 16)
+            .planted_ratio(1.0)
+            .generate()
+            .unwrap();
+        for tile in world.tiles() {
+            let entity = tile.entity().unwrap();
+            assert!(tile.ground_type().can_have_entity(entity));
+        }
+    }
+
+    #[test]
+    fn entity_weights_choose_planted_types() {
+        let world = WorldGenerator::new(5)
+            .size(8, 8)
+            .soil_ratio(0.0)
+            .planted_ratio(1.0)
+            .entity_weights(vec![(EntityType::Tree, 1.0), (EntityType::Carrot, 5.0)])
+            .unwrap()
+            .generate()
+            .unwrap();
+        assert!(world
+            .tiles()
+            .all(|tile| tile.entity().unwrap().entity_type() == &EntityType::Tree));
+    }
+
+    #[test]
+    fn entities_are_rolled_like_planted_ones() {
+        let world = WorldGenerator::new(2)
+            .size(8, 8)
+            .planted_ratio(1.0)
+            .entity_weights(vec![
+                (EntityType::Sunflower, 1.0),
+                (EntityType::Cactus, 1.0),
+            ])
+            .unwrap()
+            .growth_variance(0.5)
+            .generate()
+            .unwrap();
+        let entities: Vec<_> = world.tiles().filter_map(|tile| tile.entity()).collect();
+        assert!(!entities.is_empty());
+        for entity in &entities {
+            let measure = entity.measure().unwrap();
+            match entity.entity_type() {
+                EntityType::Sunflower => assert!((consts::SUNFLOWER_MIN_PETALS
+                    ..=consts::SUNFLOWER_MAX_PETALS)
+                    .contains(&measure)),
+                _ => {
+                    assert!((consts::CACTUS_MIN_SIZE..=consts::CACTUS_MAX_SIZE).contains(&measure))
+                }
+            }
+        }
+        assert!(entities
+            .iter()
+            .any(|entity| entity.growth_time() != entity.entity_type().growth_time()));
+    }
+
+    #[test]
+    fn pumpkins_can_generate_withering() {
+        let world = WorldGenerator::new(2)
+            .size(4, 4)
+            .soil_ratio(1.0)
+            .planted_ratio(1.0)
+            .entity_weights(vec![(EntityType::Pumpkin, 1.0)])
+            .unwrap()
+            .pumpkin_death_chance(1.0)
+            .generate()
+            .unwrap();
+        assert!(world.tiles().all(|tile| tile.entity().unwrap().withers()));
+    }
+
+    #[test]
+    fn cannot_weight_unplantable_entities() {
+        for entity_type in [EntityType::Apple, EntityType::Hedge, EntityType::Treasure] {
+            assert!(matches!(
+                WorldGenerator::new(2).entity_weights(vec![(entity_type.clone(), 1.0)]),
+                Err(EngineError::EntityNotPlantable(rejected)) if rejected == entity_type
+            ));
+        }
+    }
+
+    #[test]
+    fn larger_patches_cluster_ground_types() {
+        let generate = |patch_size| {
+            WorldGenerator::new(9)
+                .size(32, 32)
+                .soil_ratio(0.5)
+                .patch_size(patch_size)
+                .generate()
+                .unwrap()
+        };
+        assert!(matching_neighbors(&generate(8)) > matching_neighbors(&generate(1)));
+    }
+}
@@ -1,9 +1,13 @@
+use std::time::Duration;
+
+use crate::consts;
 use crate::error::EngineError;
 use crate::world::entities::{Entity, EntityType};
 
 pub type TileId = usize;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GroundType {
     Grassland,
     Soil,
@@ -11,21 +15,38 @@ pub enum GroundType {
 
 impl GroundType {
     pub fn can_have_entity(&self, entity: &Entity) -> bool {
+        if entity.entity_type() == &EntityType::Apple {
+            return true;
+        }
         match self {
-            GroundType::Grassland => [EntityType::Grass, EntityType::Bush, EntityType::Tree]
-                .contains(entity.entity_type()),
-            GroundType::Soil => {
-                [EntityType::Carrot, EntityType::Pumpkin].contains(entity.entity_type())
-            }
+            GroundType::Grassland => [
+                EntityType::Grass,
+                EntityType::Bush,
+                EntityType::Tree,
+                EntityType::Hedge,
+                EntityType::Treasure,
+            ]
+            .contains(entity.entity_type()),
+            GroundType::Soil => [
+                EntityType::Carrot,
+                EntityType::Pumpkin,
+                EntityType::Sunflower,
+                EntityType::Cactus,
+                EntityType::DeadPumpkin,
+            ]
+            .contains(entity.entity_type()),
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tile {
     id: TileId,
     ground_type: GroundType,
     entity: Option<Entity>,
+    water: f64,
+    water_updated_at: Duration,
 }
 
 impl Tile {
@@ -51,6 +72,80 @@ impl Tile {
         self.entity = entity;
         Ok(self)
     }
+    pub fn mut_entity(&mut self) -> Option<&mut Entity> {
+        self.entity.as_mut()
+    }
+    pub fn take_entity(&mut self) -> Option<Entity> {
+        self.entity.take()
+    }
+    pub fn water(&self, now: Duration) -> f64 {
+        let elapsed = now.saturating_sub(self.water_updated_at).as_secs_f64();
+        (self.water - consts::WATER_DECAY_PER_SECOND * elapsed).max(0.0)
+    }
+    pub fn set_water(&mut self, water: f64, now: Duration) -> &mut Self {
+        self.settle_growth(now);
+        self.water = water.clamp(0.0, consts::MAX_WATER);
+        self.water_updated_at = now;
+        self
+    }
+    pub fn add_water(&mut self, amount: f64, now: Duration) -> &mut Self {
+        let water = self.water(now) + amount;
+        self.set_water(water, now)
+    }
+    /// Growth an entity on this tile gains between `from` and `to`, sped up
+    /// by the (decaying) water level over that span.
+    fn growth_between(&self, from: Duration, to: Duration) -> Duration {
+        let elapsed = to.saturating_sub(from).as_secs_f64();
+        let water = self.water(from);
+        let wet = (water / consts::WATER_DECAY_PER_SECOND).min(elapsed);
+        let water_seconds = water * wet - 0.5 * consts::WATER_DECAY_PER_SECOND * wet * wet;
+        Duration::from_secs_f64(elapsed + consts::WATER_GROWTH_BONUS * water_seconds)
+    }
+    /// Folds the growth accumulated since the entity was last updated into
+    /// its progress. Must be called before anything that changes growth speed.
+    pub fn settle_growth(&mut self, now: Duration) -> &mut Self {
+        if let Some(updated_at) = self.entity.as_ref().map(Entity::updated_at) {
+            let growth = self.growth_between(updated_at, now);
+            if let Some(entity) = self.entity.as_mut() {
+                entity.grow(growth, now);
+            }
+        }
+        self
+    }
+    /// Settles growth and turns a fully grown withering entity into a dead
+    /// pumpkin.
+    pub fn refresh(&mut self, now: Duration) -> &mut Self {
+        self.settle_growth(now);
+        if let Some(entity) = self.entity.as_mut() {
+            if entity.withers() && entity.is_grown() {
+                entity
+                    .set_entity_type(EntityType::DeadPumpkin)
+                    .set_withers(false);
+            }
+        }
+        self
+    }
+    /// Earliest time after `now` the entity could finish growing. Water
+    /// only decays, so it grows no faster than at `now` until the tile
+    /// changes.
+    pub(crate) fn earliest_grown_at(&self, now: Duration) -> Option<Duration> {
+        let entity = self.entity.as_ref()?;
+        let grown = entity.progress() + self.growth_between(entity.updated_at(), now);
+        let remaining = entity.growth_time().saturating_sub(grown);
+        let fastest = 1.0 + consts::WATER_GROWTH_BONUS * self.water(now);
+        // Rounded down so float error cannot push the bound past the moment
+        // `is_grown` turns true.
+        let wait = remaining
+            .div_f64(fastest)
+            .saturating_sub(Duration::from_micros(1));
+        Some(now + wait)
+    }
+    pub fn is_grown(&self, now: Duration) -> bool {
+        self.entity.as_ref().is_some_and(|entity| {
+            entity.progress() + self.growth_between(entity.updated_at(), now)
+                >= entity.growth_time()
+        })
+    }
 }
 
 pub struct TileBuilder {
@@ -96,6 +191,8 @@ impl TileBuilder {
             id: self.id,
             ground_type,
             entity,
+            water: 0.0,
+            water_updated_at: Duration::ZERO,
         })
     }
 }
@@ -137,4 +234,89 @@ mod tests {
             EngineError::EntityNotAllowedOnGroundType(EntityType::Carrot, GroundType::Grassland)
         ));
     }
+
+    fn tile_with(entity_type: EntityType, planted_at: Duration) -> Tile {
+        TileBuilder::new(0)
+            .ground_type(GroundType::Grassland)
+            .entity(Some(Entity::new(entity_type, planted_at)))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn unwatered_entity_grows_at_base_speed() {
+        let tile = tile_with(EntityType::Tree, Duration::ZERO);
+        let growth_time = EntityType::Tree.growth_time();
+
+        assert!(!tile.is_grown(growth_time - Duration::from_millis(1)));
+        assert!(tile.is_grown(growth_time));
+    }
+
+    #[test]
+    fn growth_does_not_wrap_after_a_day() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let planted_at = day - Duration::from_secs(2);
+        let tile = tile_with(EntityType::Tree, planted_at);
+
+        assert!(!tile.is_grown(planted_at));
+        assert!(!tile.is_grown(day));
+        assert!(tile.is_grown(day + Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn withering_pumpkin_dies_once_grown() {
+        let mut tile = TileBuilder::new(0)
+            .ground_type(GroundType::Soil)
+            .entity(Some(EntityType::Pumpkin.into()))
+            .build()
+            .unwrap();
+        tile.mut_entity().unwrap().set_withers(true);
+
+        tile.refresh(Duration::from_secs(1));
+        assert_eq!(tile.entity().unwrap().entity_type(), &EntityType::Pumpkin);
+
+        tile.refresh(EntityType::Pumpkin.growth_time());
+        assert_eq!(
+            tile.entity().unwrap().entity_type(),
+            &EntityType::DeadPumpkin
+        );
+    }
+
+    #[test]
+    fn water_decays_over_time() {
+        let mut tile = tile_with(EntityType::Grass, Duration::ZERO);
+        tile.add_water(0.5, Duration::ZERO);
+
+        assert_eq!(tile.water(Duration::ZERO), 0.5);
+        assert!((tile.water(Duration::from_secs(10)) - 0.4).abs() < 1e-9);
+        assert_eq!(tile.water(Duration::from_secs(100)), 0.0);
+    }
+
+    #[test]
+    fn water_is_capped() {
+        let mut tile = tile_with(EntityType::Grass, Duration::ZERO);
+        tile.add_water(0.75, Duration::ZERO)
+            .add_water(0.75, Duration::ZERO);
+
+        assert_eq!(tile.water(Duration::ZERO), consts::MAX_WATER);
+    }
+
+    #[test]
+    fn watered_entity_grows_faster() {
+        let mut tile = tile_with(EntityType::Tree, Duration::ZERO);
+        tile.add_water(consts::MAX_WATER, Duration::ZERO);
+
+        // Nearly full water for the whole span grows roughly five times faster.
+        assert!(tile.is_grown(Duration::from_secs_f64(1.1)));
+        assert!(!tile.is_grown(Duration::from_secs_f64(0.9)));
+    }
+
+    #[test]
+    fn settling_growth_preserves_progress_when_water_changes() {
+        let mut tile = tile_with(EntityType::Tree, Duration::ZERO);
+
+        tile.add_water(consts::MAX_WATER, Duration::from_secs(4));
+        assert!(tile.entity().unwrap().progress() >= Duration::from_secs(4));
+        assert!(tile.is_grown(Duration::from_secs_f64(4.3)));
+    }
 }